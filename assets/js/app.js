@@ -0,0 +1,24 @@
+// Entry point, loaded as a module by all pages
+
+import * as board from "./board.js";
+import * as config from "./config.js";
+import * as edit from "./edit.js";
+import * as keys from "./keys.js";
+import * as live from "./live.js";
+import * as offline from "./offline.js";
+import * as reports from "./reports.js";
+import * as shell from "./shell.js";
+
+// dates in local timezone, shown on hover
+for (const td of document.querySelectorAll("td[data-ts]")) {
+  td.title = new Date(td.dataset.ts).toLocaleString();
+}
+
+edit.init();
+board.init();
+shell.init();
+reports.init();
+config.init();
+offline.init();
+keys.init();
+live.init();
@@ -0,0 +1,37 @@
+// Board drag & drop, and calendar
+
+import { errorMessage, on, reload, request, showMessage, unmodifiedSince } from "./dom.js";
+
+export function init() {
+  on("dragstart", ".board [data-uuid]", function (e) {
+    e.dataTransfer.setData("text/plain", this.dataset.uuid);
+  });
+
+  on("dragover", ".board [data-value]", function (e) {
+    e.preventDefault();
+  });
+
+  on("drop", ".board [data-value]", async function (e) {
+    e.preventDefault();
+    const uuid = e.dataTransfer.getData("text/plain");
+    const attribute = this.closest(".board").dataset.attribute;
+    try {
+      await request(
+        "POST",
+        "/board/" + encodeURIComponent(attribute),
+        { uuid: uuid, value: this.dataset.value },
+        unmodifiedSince(document.querySelector('.board [data-uuid="' + uuid + '"]'))
+      );
+      reload();
+    } catch (error) {
+      showMessage(errorMessage(error));
+    }
+  });
+
+  // pre-fill add form with clicked day
+  on("click", ".calendar td[data-date]", function () {
+    const input = document.querySelector("#add input[name=args]");
+    input.value = input.value.replace(/\s*due:\S*/, "") + " due:" + this.dataset.date;
+    input.focus();
+  });
+}
@@ -0,0 +1,64 @@
+// UDAs and recurring templates
+
+import { errorMessage, field, on, reload, request, showMessage, unmodifiedSince } from "./dom.js";
+
+const UDA_FIELDS = ["name", "type", "label", "values", "default"];
+
+async function recurringRequest(e, method, url, data) {
+  const section = e.closest("section.recurring");
+  const output = section.querySelector("output");
+  try {
+    const result = await request(
+      method,
+      "/templates/recurring/" + section.dataset.uuid + url,
+      data,
+      unmodifiedSince(section)
+    );
+    if (result.body.code == 0) {
+      reload();
+    } else {
+      showMessage(result.body.output, output);
+    }
+  } catch (error) {
+    showMessage(errorMessage(error), output);
+  }
+}
+
+export function init() {
+  on("click", "button.uda-edit", function () {
+    const row = this.closest("tr");
+    const form = document.getElementById("uda");
+    for (const key of UDA_FIELDS) {
+      field(form, key).value = row.getAttribute("data-" + key);
+    }
+  });
+
+  on("submit", "form#uda", async function (e) {
+    e.preventDefault();
+    const form = this;
+    const uda = {};
+    for (const key of UDA_FIELDS) {
+      uda[key] = field(form, key).value;
+    }
+    try {
+      await request("POST", "/udas", uda);
+      reload();
+    } catch (error) {
+      showMessage(errorMessage(error), form.querySelector("output"));
+    }
+  });
+
+  on("submit", "form.recurring-modify", function (e) {
+    e.preventDefault();
+    recurringRequest(this, "POST", "", {
+      attribute: field(this, "attribute").value,
+      value: field(this, "value").value,
+      propagate: field(this, "propagate").checked,
+    });
+  });
+
+  on("click", "button.recurring-stop", function () {
+    const deleteChildren = this.dataset.deleteChildren ? "true" : "false";
+    recurringRequest(this, "DELETE", "?delete_children=" + deleteChildren);
+  });
+}
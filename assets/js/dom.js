@@ -0,0 +1,78 @@
+// DOM and HTTP helpers
+
+// delegated event listener, handler is called with the element matching selector as `this`
+export function on(type, selector, handler) {
+  document.addEventListener(type, function (e) {
+    const target = e.target instanceof Element ? e.target.closest(selector) : null;
+    if (target) {
+      handler.call(target, e);
+    }
+  });
+}
+
+// create element with attributes and children
+export function element(tag, attributes = {}, ...children) {
+  const e = document.createElement(tag);
+  for (const [name, value] of Object.entries(attributes)) {
+    e.setAttribute(name, value);
+  }
+  e.append(...children);
+  return e;
+}
+
+export function field(form, name) {
+  return form.querySelector("[name=" + name + "]");
+}
+
+export class RequestError extends Error {
+  constructor(response, body) {
+    super(response.statusText);
+    this.status = response.status;
+    this.body = body;
+  }
+}
+
+// JSON request, resolves with status and decoded body, rejects with RequestError
+export async function request(method, url, data, headers = {}) {
+  const init = { method: method, headers: headers };
+  if (data !== undefined) {
+    init.headers = Object.assign({ "Content-Type": "application/json" }, headers);
+    init.body = JSON.stringify(data);
+  }
+  const response = await fetch(url, init);
+  const json = (response.headers.get("Content-Type") || "").includes("json");
+  const body = json ? await response.json() : await response.text();
+  if (!response.ok) {
+    throw new RequestError(response, body);
+  }
+  return { status: response.status, body: body };
+}
+
+export function reload() {
+  document.location.reload();
+}
+
+// modification time of the displayed task, to detect concurrent edits
+export function unmodifiedSince(e) {
+  const modified = e && e.closest("[data-modified]");
+  return modified ? { "If-Unmodified-Since": new Date(modified.dataset.modified).toUTCString() } : {};
+}
+
+// describe what changed since tasks were displayed, from a 409 Conflict response
+export function conflictLines(conflicts) {
+  return conflicts.map(function (conflict) {
+    const changes = conflict.changes.map(function (change) {
+      return change.attribute + ": " + (change.old || "") + " → " + (change.new || "");
+    });
+    return conflict.description + " was modified at " + conflict.modified + " (" + changes.join(", ") + ")";
+  });
+}
+
+export function errorMessage(error) {
+  return error.status == 409 ? conflictLines(error.body).join("\n") : error.message;
+}
+
+export function showMessage(message, output = document.getElementById("sync")) {
+  output.style.whiteSpace = "pre";
+  output.textContent = message;
+}
@@ -0,0 +1,208 @@
+// Inline edition, with an editor matching the column type, and annotations
+
+import { element, errorMessage, on, reload, request, showMessage, unmodifiedSince } from "./dom.js";
+
+const EDITABLE = "td[data-attribute]:not(.ro)";
+
+async function modifyTask(e, attribute, value) {
+  try {
+    const result = await request(
+      "POST",
+      "/task/" + e.closest("[data-uuid]").dataset.uuid,
+      { attribute: attribute, value: value },
+      unmodifiedSince(e)
+    );
+    if (result.status == 202) {
+      showMessage("Offline, changes will be sent when back online");
+    } else {
+      reload();
+    }
+  } catch (error) {
+    showMessage(errorMessage(error));
+  }
+}
+
+function dateEditor(input) {
+  const picker = element("input", { type: "datetime-local", step: "1" });
+  const resolved = element("output");
+  let timeout;
+  input.addEventListener("input", function () {
+    clearTimeout(timeout);
+    timeout = setTimeout(async function () {
+      let date;
+      try {
+        const result = await request("GET", "/calc?expression=" + encodeURIComponent(input.value));
+        date = result.body.result;
+      } catch (error) {
+        // expression not complete yet
+      }
+      if (date) {
+        resolved.textContent = date;
+        picker.value = date;
+      } else {
+        resolved.textContent = "";
+      }
+    }, 300);
+  });
+  picker.addEventListener("change", function () {
+    input.value = picker.value;
+    resolved.textContent = picker.value;
+  });
+  return [picker, resolved];
+}
+
+function tagsEditor(tags) {
+  const editor = element("span", { class: "editor tags" });
+  for (const tag of tags) {
+    editor.append(
+      element("span", { class: "tag" }, tag, element("button", { type: "button", class: "tag-remove", "data-tag": tag }, "×"))
+    );
+  }
+  editor.append(element("input", { type: "text", class: "tag-add", placeholder: "new tag" }));
+  return editor;
+}
+
+function openEditor(td) {
+  if (td.querySelector(".editor")) {
+    return;
+  }
+
+  const origVal = td.textContent.trim();
+  let editor;
+  if (td.dataset.attribute == "tags") {
+    editor = tagsEditor(origVal.split(/[\s,]+/).filter(Boolean));
+  } else {
+    const input = element("input", { type: "text", class: "value" });
+    input.value = origVal;
+    if (td.classList.contains("num")) {
+      input.type = "number";
+      input.step = "any";
+    }
+    editor = element("span", { class: "editor" }, input);
+    if (td.classList.contains("dt")) {
+      editor.append(...dateEditor(input));
+    }
+  }
+  editor.dataset.origVal = origVal;
+  td.replaceChildren(editor);
+  editor.querySelector("input").focus();
+}
+
+export function init() {
+  // editable cells are reachable with Tab, and open with Enter
+  for (const td of document.querySelectorAll(EDITABLE)) {
+    td.tabIndex = 0;
+  }
+
+  on("click", EDITABLE, function () {
+    openEditor(this);
+  });
+
+  on("keydown", EDITABLE, function (e) {
+    if (e.target == this && e.key == "Enter") {
+      e.preventDefault();
+      openEditor(this);
+    }
+  });
+
+  on("keydown", "td .editor input", function (e) {
+    const editor = this.closest(".editor");
+    if (e.key == "Escape") {
+      editor.dataset.cancelled = "true";
+    } else if (e.key != "Enter" || !this.classList.contains("value")) {
+      return;
+    }
+    // keep keyboard focus on the cell
+    editor.dataset.refocus = "true";
+    this.blur();
+  });
+
+  on("click", "button.tag-remove", function () {
+    modifyTask(this, "tags", "-" + this.dataset.tag);
+  });
+
+  on("keydown", "input.tag-add", function (e) {
+    if (e.key == "Enter" && this.value) {
+      modifyTask(this, "tags", "+" + this.value);
+    }
+  });
+
+  // editor unfocus
+  on("focusout", "td .editor", function (e) {
+    if ((e.relatedTarget && this.contains(e.relatedTarget)) || !this.isConnected) {
+      return;
+    }
+    const td = this.closest("td");
+    const origVal = this.dataset.origVal;
+    const input = this.querySelector("input.value");
+    const value = input && !this.dataset.cancelled ? input.value : origVal;
+    if (value != origVal) {
+      modifyTask(td, td.dataset.attribute, value);
+    }
+    td.replaceChildren(value);
+    if (this.dataset.refocus) {
+      td.focus();
+    }
+  });
+
+  // annotations
+  async function annotationRequest(e, method, data) {
+    try {
+      await request(
+        method,
+        "/task/" + e.closest("[data-uuid]").dataset.uuid + "/annotations",
+        data,
+        unmodifiedSince(e)
+      );
+      reload();
+    } catch (error) {
+      showMessage(errorMessage(error));
+    }
+  }
+
+  // annotation row of tasks without annotations, only created when needed
+  on("click", "button.annotation-new", function () {
+    const row = this.closest("tr");
+    const form = element(
+      "form",
+      { class: "annotation-add" },
+      element("textarea", { name: "description" }),
+      element("button", { type: "submit" }, "Annotate")
+    );
+    const annotations = element(
+      "tr",
+      { class: "annotations", "data-uuid": row.dataset.uuid },
+      element("td", { colspan: row.cells.length }, form)
+    );
+    if (row.dataset.modified) {
+      annotations.dataset.modified = row.dataset.modified;
+    }
+    row.after(annotations);
+    this.remove();
+    form.querySelector("textarea").focus();
+  });
+
+  on("submit", "form.annotation-add", function (e) {
+    e.preventDefault();
+    annotationRequest(this, "POST", { description: this.querySelector("textarea").value });
+  });
+
+  on("click", "button.annotation-remove", function () {
+    annotationRequest(this, "DELETE", { description: this.closest("li").dataset.description });
+  });
+
+  on("click", "button.annotation-edit", function () {
+    const li = this.closest("li");
+    const old = li.dataset.description;
+    const textarea = element("textarea");
+    textarea.value = old;
+    const save = element("button", { type: "button" }, "save");
+    save.addEventListener("click", function () {
+      annotationRequest(save, "PUT", { old: old, description: textarea.value });
+    });
+    for (const e of li.querySelectorAll("span, button")) {
+      e.remove();
+    }
+    li.append(textarea, save);
+  });
+}
@@ -0,0 +1,129 @@
+// Keyboard navigation, with the keymap read from /keymap
+
+import { errorMessage, reload, request, showMessage } from "./dom.js";
+import * as palette from "./palette.js";
+
+const ROWS = "tbody tr[data-uuid]:not(.annotations)";
+
+function selectedRow() {
+  return document.querySelector(ROWS + ".selected");
+}
+
+function select(offset) {
+  const rows = Array.from(document.querySelectorAll(ROWS));
+  if (rows.length == 0) {
+    return;
+  }
+  const current = rows.indexOf(selectedRow());
+  const next = current < 0 ? 0 : Math.min(Math.max(current + offset, 0), rows.length - 1);
+  if (current >= 0) {
+    rows[current].classList.remove("selected");
+  }
+  rows[next].classList.add("selected");
+  rows[next].scrollIntoView({ block: "nearest" });
+}
+
+async function shell(command) {
+  try {
+    await request("POST", "/shell", command);
+    reload();
+  } catch (error) {
+    showMessage(errorMessage(error));
+  }
+}
+
+const ACTIONS = {
+  down: function () {
+    select(1);
+  },
+  up: function () {
+    select(-1);
+  },
+  edit: function () {
+    const row = selectedRow();
+    const cell = row && row.querySelector("td[data-attribute]:not(.ro)");
+    if (cell) {
+      cell.click();
+    }
+  },
+  done: async function () {
+    const row = selectedRow();
+    if (!row) {
+      return;
+    }
+    const modified = {};
+    if (row.dataset.modified) {
+      modified[row.dataset.uuid] = row.dataset.modified;
+    }
+    try {
+      await request("POST", "/bulk", {
+        uuids: [row.dataset.uuid],
+        modified: modified,
+        action: "done",
+      });
+      reload();
+    } catch (error) {
+      showMessage(errorMessage(error));
+    }
+  },
+  add: function () {
+    palette.open(":add ");
+  },
+  filter: function () {
+    const filter = document.querySelector("#view [name=filter]");
+    if (filter) {
+      filter.focus();
+    }
+  },
+  shell: function () {
+    palette.open(":");
+  },
+  undo: function () {
+    shell("rc.confirmation:off undo");
+  },
+  palette: function () {
+    palette.open();
+  },
+};
+
+// key of an event, as written in the keymap: shift is already applied to character keys
+function eventKey(e) {
+  const shift = e.shiftKey && e.key.length > 1;
+  return (e.ctrlKey ? "ctrl+" : "") + (e.altKey ? "alt+" : "") + (shift ? "shift+" : "") + e.key;
+}
+
+export async function init() {
+  let keymap;
+  try {
+    keymap = (await request("GET", "/keymap")).body;
+  } catch (error) {
+    showMessage(errorMessage(error));
+    return;
+  }
+  const bindings = {};
+  for (const binding of keymap) {
+    bindings[binding.key] = binding.action;
+  }
+
+  palette.setActions(
+    keymap
+      .filter(function (binding) {
+        return binding.action != "palette";
+      })
+      .map(function (binding) {
+        return { label: binding.description, hint: binding.key, run: ACTIONS[binding.action] };
+      })
+  );
+
+  document.addEventListener("keydown", function (e) {
+    // leave meta shortcuts (like Cmd+D on macOS) to the browser
+    if (e.metaKey || e.target.closest("input, textarea, select, dialog, [contenteditable]")) {
+      return;
+    }
+    const action = ACTIONS[bindings[eventKey(e)]];
+    if (action) {
+      e.preventDefault();
+      action();
+    }
+  });
+}
@@ -0,0 +1,51 @@
+// Live updates: poll the task data version, and reload when tasks are changed elsewhere
+
+import { reload, request, showMessage } from "./dom.js";
+
+const POLL_INTERVAL_MS = 10000;
+
+let version;
+
+async function dataVersion() {
+  return (await request("GET", "/updates")).body.version;
+}
+
+// reloading would lose an edit in progress or a displayed message
+function busy() {
+  const active = document.activeElement;
+  return (
+    document.querySelector(".editor, dialog[open]") ||
+    (active && active.matches("input, textarea, select")) ||
+    document.getElementById("sync").textContent
+  );
+}
+
+async function poll() {
+  if (document.hidden) {
+    return;
+  }
+  try {
+    const latest = await dataVersion();
+    if (latest == version) {
+      return;
+    }
+    version = latest;
+    if (busy()) {
+      showMessage("Tasks were changed, reload the page to see them", document.getElementById("updates"));
+    } else {
+      reload();
+    }
+  } catch (error) {
+    // offline, retried at next poll
+  }
+}
+
+export async function init() {
+  try {
+    version = await dataVersion();
+  } catch (error) {
+    return;
+  }
+  setInterval(poll, POLL_INTERVAL_MS);
+  document.addEventListener("visibilitychange", poll);
+}
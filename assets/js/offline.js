@@ -0,0 +1,32 @@
+// Service worker registration, and report of offline changes replayed when back online
+
+import { conflictLines, showMessage } from "./dom.js";
+
+export function init() {
+  if (!("serviceWorker" in navigator)) {
+    return;
+  }
+  // versioned prefix of this module URL, so the worker precaches current assets
+  const prefix = new URL("../", import.meta.url).pathname;
+  navigator.serviceWorker.register("/sw.js?static=" + encodeURIComponent(prefix));
+  // offline changes not applied, reported once the queue is replayed
+  let rejected = [];
+  navigator.serviceWorker.addEventListener("message", function (e) {
+    const path = e.data.entry ? e.data.entry.method + " " + new URL(e.data.entry.url).pathname : "";
+    if (e.data.type == "conflict") {
+      rejected = rejected.concat(conflictLines(e.data.conflicts));
+    } else if (e.data.type == "failed") {
+      rejected.push(path + ": " + e.data.status + " " + e.data.statusText);
+    } else if (e.data.type == "skipped") {
+      rejected.push(path + ": skipped after conflict");
+    } else if (e.data.type == "replayed" && e.data.count > 0) {
+      const summary = e.data.count + " offline change(s) replayed";
+      showMessage(rejected.length ? [summary + ", not applied:"].concat(rejected).join("\n") : summary);
+      rejected = [];
+    }
+  });
+  window.addEventListener("online", async function () {
+    const registration = await navigator.serviceWorker.ready;
+    registration.active.postMessage({ type: "replay" });
+  });
+}
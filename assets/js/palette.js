@@ -0,0 +1,134 @@
+// Command palette, listing keyboard actions and reports, or running task commands when input starts with ":"
+
+import { element, errorMessage, request } from "./dom.js";
+
+let dialog;
+let input;
+let list;
+let output;
+let actions = [];
+let reports = [];
+// page data is stale once a command ran
+let stale = false;
+
+function render() {
+  const query = input.value.trim().toLowerCase();
+  list.replaceChildren();
+  if (input.value.startsWith(":")) {
+    return;
+  }
+  const matching = actions.concat(reports).filter(function (entry) {
+    return entry.label.toLowerCase().includes(query);
+  });
+  matching.forEach(function (entry, i) {
+    const li = element("li", {}, entry.label);
+    if (entry.hint) {
+      li.append(" ", element("kbd", {}, entry.hint));
+    }
+    li.classList.toggle("active", i == 0);
+    li.addEventListener("click", function () {
+      close();
+      entry.run();
+    });
+    li.entry = entry;
+    list.append(li);
+  });
+}
+
+function move(offset) {
+  const items = Array.from(list.children);
+  const current = items.findIndex(function (li) {
+    return li.classList.contains("active");
+  });
+  if (current < 0) {
+    return;
+  }
+  const next = Math.min(Math.max(current + offset, 0), items.length - 1);
+  items[current].classList.remove("active");
+  items[next].classList.add("active");
+  items[next].scrollIntoView({ block: "nearest" });
+}
+
+async function runShell(command) {
+  try {
+    const result = await request("POST", "/shell", command);
+    stale = true;
+    output.textContent = result.body.output;
+  } catch (error) {
+    output.textContent = errorMessage(error);
+  }
+}
+
+function submit() {
+  if (input.value.startsWith(":")) {
+    runShell(input.value.slice(1).trim());
+    return;
+  }
+  const active = list.querySelector("li.active");
+  if (active) {
+    close();
+    active.entry.run();
+  }
+}
+
+function create() {
+  input = element("input", { type: "text", placeholder: "action, report, or :task command" });
+  list = element("ul");
+  output = element("pre");
+  const form = element("form", { method: "dialog" }, input, list, output);
+  dialog = element("dialog", { id: "palette" }, form);
+  document.body.append(dialog);
+
+  input.addEventListener("input", render);
+  input.addEventListener("keydown", function (e) {
+    if (e.key == "ArrowDown" || e.key == "ArrowUp") {
+      e.preventDefault();
+      move(e.key == "ArrowDown" ? 1 : -1);
+    }
+  });
+  form.addEventListener("submit", function (e) {
+    e.preventDefault();
+    submit();
+  });
+  dialog.addEventListener("close", function () {
+    if (stale) {
+      document.location.reload();
+    }
+  });
+}
+
+function close() {
+  dialog.close();
+}
+
+// entries with a label, an optional key hint, and a run function
+export function setActions(entries) {
+  actions = entries;
+}
+
+export async function open(value = "") {
+  if (!dialog) {
+    create();
+  }
+  output.textContent = "";
+  input.value = value;
+  render();
+  dialog.showModal();
+  input.focus();
+
+  if (reports.length == 0) {
+    try {
+      reports = (await request("GET", "/reports/list")).body.map(function (info) {
+        return {
+          label: "report " + info.name + (info.description ? ": " + info.description : ""),
+          run: function () {
+            document.location = "/" + info.name;
+          },
+        };
+      });
+      render();
+    } catch (error) {
+      output.textContent = errorMessage(error);
+    }
+  }
+}
@@ -0,0 +1,114 @@
+// Report selection, saved views, and report editor with live preview
+
+import { element, errorMessage, field, on, request, showMessage } from "./dom.js";
+
+function reportEdit(form, preview) {
+  const list = function (name) {
+    return field(form, name).value.split(",");
+  };
+  return request("POST", "/reports", {
+    name: field(form, "name").value,
+    description: field(form, "description").value,
+    columns: list("columns"),
+    labels: list("labels"),
+    sort: field(form, "sort").value,
+    filter: field(form, "filter").value,
+    preview: preview,
+  });
+}
+
+async function reportPreview(form) {
+  const output = form.querySelector("output");
+  let result;
+  try {
+    result = (await reportEdit(form, true)).body;
+  } catch (error) {
+    showMessage(errorMessage(error), output);
+    return;
+  }
+  showMessage(result.errors.join("\n"), output);
+  const preview = document.getElementById("report-preview");
+  preview.replaceChildren();
+  if (!result.report) {
+    return;
+  }
+  const head = element(
+    "tr",
+    {},
+    ...result.report.labels.map(function (label) {
+      return element("th", {}, label);
+    })
+  );
+  const body = element(
+    "tbody",
+    {},
+    ...result.report.tasks.map(function (task) {
+      return element(
+        "tr",
+        {},
+        ...task.attributes.map(function (attribute) {
+          return element("td", {}, attribute);
+        })
+      );
+    })
+  );
+  preview.append(element("table", {}, element("thead", {}, head), body));
+}
+
+export function init() {
+  on("change", "select#reports", function () {
+    document.location = "/" + this.value;
+  });
+
+  on("submit", "form#save-view", async function (e) {
+    e.preventDefault();
+    const form = this;
+    const view = document.getElementById("view");
+    try {
+      await request("POST", "/views", {
+        name: field(form, "name").value,
+        description: field(form, "description").value,
+        report: form.dataset.report,
+        overwrite: field(form, "overwrite").checked,
+        filter: field(view, "filter").value,
+        sort: field(view, "sort").value,
+        columns: field(view, "columns").value,
+      });
+      document.location = "/" + field(form, "name").value;
+    } catch (error) {
+      showMessage(errorMessage(error), form.querySelector("output"));
+    }
+  });
+
+  let previewTimeout;
+  on("input", "form#report-editor input", function () {
+    const form = this.closest("form");
+    clearTimeout(previewTimeout);
+    previewTimeout = setTimeout(function () {
+      reportPreview(form);
+    }, 500);
+  });
+
+  const editor = document.getElementById("report-editor");
+  if (editor) {
+    reportPreview(editor);
+  }
+
+  on("submit", "form#report-editor", async function (e) {
+    e.preventDefault();
+    const form = this;
+    const output = form.querySelector("output");
+    try {
+      const result = (await reportEdit(form, false)).body;
+      if (result.errors.length) {
+        showMessage(result.errors.join("\n"), output);
+      } else if (result.dry_run) {
+        showMessage("Dry run, report not saved", output);
+      } else {
+        document.location = "/" + field(form, "name").value;
+      }
+    } catch (error) {
+      showMessage(errorMessage(error), output);
+    }
+  });
+}
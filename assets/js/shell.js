@@ -0,0 +1,58 @@
+// Task commands: add form, and bulk operations on selected rows
+
+import { errorMessage, field, on, reload, request, showMessage } from "./dom.js";
+
+export function init() {
+  on("submit", "form#add", async function (e) {
+    e.preventDefault();
+    const args = field(this, "args").value.trim();
+    try {
+      await request("POST", "/shell", "add " + args);
+      reload();
+    } catch (error) {
+      showMessage(errorMessage(error));
+    }
+  });
+
+  on("change", "input.select-all", function () {
+    for (const input of document.querySelectorAll("input.select")) {
+      input.checked = this.checked;
+    }
+  });
+
+  on("submit", "form#bulk", async function (e) {
+    e.preventDefault();
+    const form = this;
+    const output = form.querySelector("output");
+    const uuids = [];
+    const modified = {};
+    for (const input of document.querySelectorAll("input.select:checked")) {
+      const row = input.closest("[data-uuid]");
+      uuids.push(row.dataset.uuid);
+      if (row.dataset.modified) {
+        modified[row.dataset.uuid] = row.dataset.modified;
+      }
+    }
+    try {
+      const result = await request("POST", "/bulk", {
+        uuids: uuids,
+        modified: modified,
+        action: field(form, "action").value,
+        value: field(form, "value").value,
+        preview: field(form, "preview").checked,
+      });
+      if (result.body.dry_run) {
+        const lines = [result.body.command.join(" ")].concat(
+          result.body.tasks.map(function (task) {
+            return task.description;
+          })
+        );
+        showMessage(lines.join("\n"), output);
+      } else {
+        reload();
+      }
+    } catch (error) {
+      showMessage(errorMessage(error), output);
+    }
+  });
+}
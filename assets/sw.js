@@ -0,0 +1,234 @@
+// Service worker: offline report pages and queued mutations
+"use strict";
+
+// assets version, substituted when served, so upgrades drop pages cached by previous versions
+var CACHE = "task-board-__VERSION__";
+// versioned static URL prefix, passed by the page registering the worker
+var STATIC = new URL(self.location).searchParams.get("static") || "/static/";
+var PRECACHE = ["/"].concat(
+  [
+    "js/app.js",
+    "js/board.js",
+    "js/config.js",
+    "js/dom.js",
+    "js/edit.js",
+    "js/keys.js",
+    "js/live.js",
+    "js/offline.js",
+    "js/palette.js",
+    "js/reports.js",
+    "js/shell.js",
+    "mvp.css",
+    "simple.css",
+    "water.css",
+    "theme.css",
+    "favicon.ico",
+    "manifest.json",
+  ].map(function (path) {
+    return STATIC + path;
+  })
+);
+var QUEUE_STORE = "queue";
+
+// mutation queue, stored in IndexedDB to survive restarts
+function openQueue() {
+  return new Promise(function (resolve, reject) {
+    var request = indexedDB.open("task-board", 1);
+    request.onupgradeneeded = function () {
+      request.result.createObjectStore(QUEUE_STORE, { keyPath: "id", autoIncrement: true });
+    };
+    request.onsuccess = function () {
+      resolve(request.result);
+    };
+    request.onerror = function () {
+      reject(request.error);
+    };
+  });
+}
+
+function queueTransaction(mode, action) {
+  return openQueue().then(function (db) {
+    return new Promise(function (resolve, reject) {
+      var transaction = db.transaction(QUEUE_STORE, mode);
+      var request = action(transaction.objectStore(QUEUE_STORE));
+      transaction.oncomplete = function () {
+        resolve(request.result);
+      };
+      transaction.onerror = function () {
+        reject(transaction.error);
+      };
+    });
+  });
+}
+
+function enqueue(request) {
+  return request.text().then(function (body) {
+    return queueTransaction("readwrite", function (store) {
+      return store.add({
+        url: request.url,
+        method: request.method,
+        contentType: request.headers.get("Content-Type"),
+        unmodifiedSince: request.headers.get("If-Unmodified-Since"),
+        body: body,
+      });
+    });
+  });
+}
+
+function notifyClients(message) {
+  return self.clients.matchAll().then(function (clients) {
+    clients.forEach(function (client) {
+      client.postMessage(message);
+    });
+  });
+}
+
+// replay queued mutations in order, stop at the first network failure
+// rejected mutations are reported to clients, since they are removed from the queue
+function replay() {
+  return queueTransaction("readonly", function (store) {
+    return store.getAll();
+  }).then(function (entries) {
+    // task URL -> "applied" or "conflict"
+    var results = {};
+    return entries
+      .reduce(function (previous, entry) {
+        return previous.then(function () {
+          if (results[entry.url] == "conflict") {
+            // made on the same outdated task
+            return notifyClients({ type: "skipped", entry: entry }).then(function () {
+              return queueTransaction("readwrite", function (store) {
+                return store.delete(entry.id);
+              });
+            });
+          }
+          var headers = { "Content-Type": entry.contentType || "application/json" };
+          // only the first applied edit of a task is checked, the following ones are ours
+          if (entry.unmodifiedSince && results[entry.url] != "applied") {
+            headers["If-Unmodified-Since"] = entry.unmodifiedSince;
+          }
+          return fetch(entry.url, {
+            method: entry.method,
+            headers: headers,
+            body: entry.body || undefined,
+          }).then(function (response) {
+            if (response.status == 409) {
+              results[entry.url] = "conflict";
+              return response.json().then(function (conflicts) {
+                return notifyClients({ type: "conflict", entry: entry, conflicts: conflicts });
+              });
+            }
+            if (!response.ok) {
+              return notifyClients({
+                type: "failed",
+                entry: entry,
+                status: response.status,
+                statusText: response.statusText,
+              });
+            }
+            results[entry.url] = "applied";
+          }).then(function () {
+            return queueTransaction("readwrite", function (store) {
+              return store.delete(entry.id);
+            });
+          });
+        });
+      }, Promise.resolve())
+      .then(function () {
+        return notifyClients({ type: "replayed", count: entries.length });
+      });
+  });
+}
+
+self.addEventListener("install", function (event) {
+  event.waitUntil(
+    caches
+      .open(CACHE)
+      .then(function (cache) {
+        return cache.addAll(PRECACHE);
+      })
+      .then(function () {
+        return self.skipWaiting();
+      })
+  );
+});
+
+self.addEventListener("activate", function (event) {
+  event.waitUntil(
+    caches
+      .keys()
+      .then(function (keys) {
+        return Promise.all(
+          keys
+            .filter(function (key) {
+              return key != CACHE;
+            })
+            .map(function (key) {
+              return caches.delete(key);
+            })
+        );
+      })
+      .then(function () {
+        return self.clients.claim();
+      })
+  );
+});
+
+self.addEventListener("fetch", function (event) {
+  var request = event.request;
+  if (new URL(request.url).origin != self.location.origin) {
+    return;
+  }
+
+  if (request.method == "GET" && STATIC != "/static/" && new URL(request.url).pathname.startsWith(STATIC)) {
+    // versioned assets never change
+    event.respondWith(
+      caches.match(request).then(function (cached) {
+        return cached || fetch(request);
+      })
+    );
+  } else if (request.method == "GET") {
+    // network first, so pages are fresh when online
+    event.respondWith(
+      fetch(request)
+        .then(function (response) {
+          if (response.ok) {
+            var copy = response.clone();
+            caches.open(CACHE).then(function (cache) {
+              cache.put(request, copy);
+            });
+          }
+          return response;
+        })
+        .catch(function () {
+          return caches.match(request).then(function (cached) {
+            return cached || Response.error();
+          });
+        })
+    );
+  } else {
+    var copy = request.clone();
+    event.respondWith(
+      fetch(request).catch(function () {
+        return enqueue(copy).then(function () {
+          return new Response(JSON.stringify({ queued: true, output: "", code: 0 }), {
+            status: 202,
+            headers: { "Content-Type": "application/json" },
+          });
+        });
+      })
+    );
+  }
+});
+
+self.addEventListener("sync", function (event) {
+  if (event.tag == "replay") {
+    event.waitUntil(replay());
+  }
+});
+
+self.addEventListener("message", function (event) {
+  if (event.data && event.data.type == "replay") {
+    event.waitUntil(replay());
+  }
+});
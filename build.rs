@@ -1,17 +1,144 @@
-fn download(url: &str, target_filepath: &str) -> anyhow::Result<()> {
-    let mut response = reqwest::blocking::get(url)?;
-    let mut target = std::fs::File::create(target_filepath)?;
-    std::io::copy(&mut response, &mut target)?;
+/// Third party assets, committed in vendor/ by vendor/update.sh
+static VENDORED_ASSETS: [&str; 4] = ["favicon.ico", "mvp.css", "simple.css", "water.css"];
+
+/// Parse `sha256sum` output
+fn checksums(filepath: &str) -> anyhow::Result<std::collections::HashMap<String, String>> {
+    Ok(std::fs::read_to_string(filepath)?
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.split_whitespace();
+            let checksum = parts.next()?.to_string();
+            Some((parts.next()?.trim_start_matches('*').to_string(), checksum))
+        })
+        .collect())
+}
+
+/// Read vendored asset after checking its checksum
+fn read_vendored(
+    filename: &str,
+    checksums: &std::collections::HashMap<String, String>,
+) -> anyhow::Result<Vec<u8>> {
+    use sha2::Digest;
+
+    let data = std::fs::read(format!("vendor/{}", filename)).map_err(|e| {
+        anyhow::anyhow!(
+            "Missing vendored asset vendor/{} ({}), run vendor/update.sh",
+            filename,
+            e
+        )
+    })?;
+    let checksum = format!("{:x}", sha2::Sha256::digest(&data));
+    match checksums.get(filename) {
+        Some(expected) if *expected == checksum => Ok(data),
+        Some(expected) => Err(anyhow::anyhow!(
+            "Checksum mismatch for vendor/{}: expected {}, got {}",
+            filename,
+            expected,
+            checksum
+        )),
+        None => Err(anyhow::anyhow!(
+            "No checksum for vendor/{} in vendor/SHA256SUMS",
+            filename
+        )),
+    }
+}
+
+/// Extensions of assets worth compressing
+static COMPRESSIBLE_EXTENSIONS: [&str; 5] = ["css", "ico", "js", "json", "svg"];
+
+fn asset_files(dir: &std::path::Path) -> anyhow::Result<Vec<std::path::PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(asset_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Get gzip and brotli variants of an asset, only if they are smaller
+fn compress(filepath: &str, data: &[u8]) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+    let compressible = std::path::Path::new(filepath)
+        .extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .map_or(false, |e| COMPRESSIBLE_EXTENSIONS.contains(&e));
+    if !compressible {
+        return Ok(vec![]);
+    }
+
+    let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    std::io::Write::write_all(&mut gz, data)?;
+    let gz = gz.finish()?;
+
+    let mut br = Vec::new();
+    let params = brotli::enc::BrotliEncoderParams {
+        quality: 11,
+        ..Default::default()
+    };
+    let mut input = data;
+    brotli::BrotliCompress(&mut input, &mut br, &params)?;
+
+    Ok(vec![("gz", gz), ("br", br)]
+        .into_iter()
+        .filter(|(_, compressed)| compressed.len() < data.len())
+        .map(|(ext, compressed)| (format!("{}.{}", filepath, ext), compressed))
+        .collect())
+}
+
+/// Write vendored assets and precompressed variants of all assets to `OUT_DIR`, and the Rust table
+/// embedding them, included by src/assets.rs
+fn generate_assets(out_dir: &std::path::Path) -> anyhow::Result<()> {
+    let checksums = checksums("vendor/SHA256SUMS")?;
+    if checksums.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No vendored assets in vendor/SHA256SUMS, run vendor/update.sh and commit its output"
+        ));
+    }
+    let mut assets = Vec::new();
+    for filename in &VENDORED_ASSETS {
+        assets.push((filename.to_string(), read_vendored(filename, &checksums)?));
+    }
+    let mut generated = assets.clone();
+    let assets_dir = std::path::Path::new("assets");
+    for path in asset_files(assets_dir)? {
+        let filepath = path
+            .strip_prefix(assets_dir)?
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid asset path {:?}", path))?
+            .replace('\\', "/");
+        assets.push((filepath, std::fs::read(&path)?));
+    }
+    for (filepath, data) in &assets {
+        generated.extend(compress(filepath, data)?);
+    }
+
+    let generated_dir = out_dir.join("assets");
+    let mut table = format!(
+        "static GENERATED_ASSETS: [(&str, &[u8]); {}] = [\n",
+        generated.len()
+    );
+    for (filepath, data) in &generated {
+        let target_filepath = generated_dir.join(filepath);
+        std::fs::create_dir_all(target_filepath.parent().unwrap())?;
+        std::fs::write(&target_filepath, data)?;
+        table.push_str(&format!(
+            "    ({:?}, include_bytes!({:?})),\n",
+            filepath, target_filepath
+        ));
+    }
+    table.push_str("];\n");
+    std::fs::write(out_dir.join("generated_assets.rs"), table)?;
     Ok(())
 }
 
 fn main() -> anyhow::Result<()> {
-    std::fs::create_dir_all("assets")?;
-    download(
-        "https://taskwarrior.org/images/favicon.ico",
-        "assets/favicon.ico",
-    )?;
-    download("https://andybrewer.github.io/mvp/mvp.css", "assets/mvp.css")?;
+    println!("cargo:rerun-if-changed=assets");
+    println!("cargo:rerun-if-changed=vendor");
+    let out_dir = std::path::PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+    generate_assets(&out_dir)?;
     let now = std::time::SystemTime::now();
     println!(
         "cargo:rustc-env=BUILD_DATETIME_HTTP_LAST_MODIFIED={}",
@@ -1,12 +1,174 @@
 #[derive(rust_embed::RustEmbed)]
 #[folder = "assets/"]
-pub struct Assets;
+struct Assets;
+
+// Vendored assets and precompressed variants, generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/generated_assets.rs"));
+
+/// Get content of an asset, from assets/ or generated by build.rs
+pub fn get(filepath: &str) -> Option<std::borrow::Cow<'static, [u8]>> {
+    Assets::get(filepath).or_else(|| {
+        GENERATED_ASSETS
+            .iter()
+            .find(|(p, _)| *p == filepath)
+            .map(|(_, d)| std::borrow::Cow::Borrowed(*d))
+    })
+}
+
+/// Paths of all assets
+pub fn iter() -> impl Iterator<Item = std::borrow::Cow<'static, str>> {
+    Assets::iter().chain(
+        GENERATED_ASSETS
+            .iter()
+            .map(|(p, _)| std::borrow::Cow::Borrowed(*p)),
+    )
+}
+
+/// Embedded drop in stylesheets, all following `prefers-color-scheme`
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::EnumVariantNames,
+    strum_macros::IntoStaticStr,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum Theme {
+    Mvp,
+    Simple,
+    Water,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::Mvp
+    }
+}
+
+impl Theme {
+    pub fn stylesheet(self) -> String {
+        format!("{}.css", <&str>::from(self))
+    }
+}
+
+/// Precompressed variants generated by build.rs, by preference order
+pub static ENCODINGS: [(&str, &str); 2] = [("br", "br"), ("gzip", "gz")];
 
 lazy_static! {
     static ref HTTP_LAST_MODIFIED_STRING: String =
         env!("BUILD_DATETIME_HTTP_LAST_MODIFIED").to_string();
     static ref HTTP_LAST_MODIFIED_TIME: std::time::SystemTime =
         httpdate::parse_http_date(&HTTP_LAST_MODIFIED_STRING).unwrap();
+    /// Content hash of each asset, excluding precompressed variants
+    static ref HASHES: std::collections::HashMap<String, String> = iter()
+        .filter(|f| !is_compressed(f))
+        .map(|f| {
+            let hash = hash(&get(&f).unwrap());
+            (f.into_owned(), hash)
+        })
+        .collect();
+    /// Hash of all assets, used as URL prefix so assets can be cached forever
+    pub static ref VERSION: String = {
+        let mut hashes: Vec<(&String, &String)> = HASHES.iter().collect();
+        hashes.sort();
+        hash(format!("{:?}", hashes).as_bytes())
+    };
+}
+
+fn hash(data: &[u8]) -> String {
+    use std::hash::Hasher;
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    hasher.write(data);
+    format!("{:016x}", hasher.finish())
+}
+
+fn is_compressed(filepath: &str) -> bool {
+    ENCODINGS
+        .iter()
+        .any(|(_, ext)| filepath.ends_with(&format!(".{}", ext)))
+}
+
+/// Versioned asset URL, for use in templates
+pub fn url(filepath: &str) -> String {
+    format!("/static/{}/{}", *VERSION, filepath)
+}
+
+/// Strong ETag for an asset, distinct for each encoding
+pub fn etag(filepath: &str, encoding: Option<&str>) -> Option<String> {
+    let hash = HASHES.get(filepath)?;
+    Some(match encoding {
+        Some(encoding) => format!("\"{}-{}\"", hash, encoding),
+        None => format!("\"{}\"", hash),
+    })
+}
+
+/// `If-None-Match` header
+pub struct IfNoneMatch(pub Option<String>);
+
+impl IfNoneMatch {
+    pub fn matches(&self, etag: &str) -> bool {
+        self.0.as_ref().map_or(false, |v| {
+            v.trim() == "*"
+                || v.split(',')
+                    .any(|t| t.trim().trim_start_matches("W/") == etag)
+        })
+    }
+}
+
+impl<'a, 'r> rocket::request::FromRequest<'a, 'r> for IfNoneMatch {
+    type Error = ();
+
+    fn from_request(
+        request: &'a rocket::request::Request<'r>,
+    ) -> rocket::request::Outcome<Self, Self::Error> {
+        rocket::request::Outcome::Success(IfNoneMatch(
+            request
+                .headers()
+                .get_one("If-None-Match")
+                .map(str::to_string),
+        ))
+    }
+}
+
+/// Encodings from `Accept-Encoding` header, excluding those with a zero quality
+pub struct AcceptEncoding(pub Vec<String>);
+
+impl AcceptEncoding {
+    pub fn accepts(&self, encoding: &str) -> bool {
+        self.0.iter().any(|e| e == encoding)
+    }
+}
+
+impl<'a, 'r> rocket::request::FromRequest<'a, 'r> for AcceptEncoding {
+    type Error = ();
+
+    fn from_request(
+        request: &'a rocket::request::Request<'r>,
+    ) -> rocket::request::Outcome<Self, Self::Error> {
+        let encodings = request
+            .headers()
+            .get("Accept-Encoding")
+            .flat_map(|h| h.split(','))
+            .filter_map(|e| {
+                let mut params = e.split(';').map(str::trim);
+                let encoding = params.next()?;
+                let rejected = params.any(|p| {
+                    p.strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .map_or(false, |q| q == 0.0)
+                });
+                if rejected || encoding.is_empty() {
+                    None
+                } else {
+                    Some(encoding.to_ascii_lowercase())
+                }
+            })
+            .collect();
+        rocket::request::Outcome::Success(AcceptEncoding(encodings))
+    }
 }
 
 pub struct IfModified<'a> {
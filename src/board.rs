@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+static STATUS_VALUES: [&str; 4] = ["pending", "active", "waiting", "completed"];
+
+/// Default priority values, if not defined as an UDA in configuration
+static PRIORITY_VALUES: [&str; 4] = ["H", "M", "L", ""];
+
+/// Completed tasks older than this are not displayed on the status board
+const COMPLETED_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(serde::Serialize)]
+pub struct BoardColumn {
+    value: String,
+    tasks: Vec<tw::ExportedTask>,
+}
+
+#[derive(serde::Serialize)]
+pub struct Board {
+    attribute: String,
+    attributes: Vec<String>,
+    columns: Vec<BoardColumn>,
+}
+
+/// Get attributes tasks can be grouped by
+fn groupable_attributes(config: &HashMap<String, String>) -> Vec<String> {
+    let mut r = vec!["status".to_string(), "project".to_string()];
+    let mut udas: Vec<String> = config
+        .keys()
+        .filter_map(|k| k.strip_prefix("uda."))
+        .filter_map(|k| k.strip_suffix(".values"))
+        .map(str::to_string)
+        .collect();
+    if !udas.iter().any(|u| u == "priority") {
+        udas.push("priority".to_string());
+    }
+    udas.sort();
+    r.extend(udas);
+    r
+}
+
+/// Check that tasks can be grouped, and so moved between board columns, by an attribute
+pub fn is_groupable(attribute: &str, options: &RunOpts) -> anyhow::Result<bool> {
+    Ok(groupable_attributes(&tw::config(options)?)
+        .iter()
+        .any(|a| a == attribute))
+}
+
+/// Get allowed values for an attribute, if restricted
+fn attribute_values(attribute: &str, config: &HashMap<String, String>) -> Option<Vec<String>> {
+    match config.get(&format!("uda.{}.values", attribute)) {
+        Some(values) => Some(values.split(',').map(str::to_string).collect()),
+        None if attribute == "priority" => {
+            Some(PRIORITY_VALUES.iter().map(|v| v.to_string()).collect())
+        }
+        None => None,
+    }
+}
+
+/// Get value of the grouped attribute for a task
+fn task_value(task: &tw::ExportedTask, attribute: &str) -> String {
+    if attribute == "status" && task.status == "pending" && task.start.is_some() {
+        "active".to_string()
+    } else {
+        task.attribute(attribute).unwrap_or_default()
+    }
+}
+
+pub fn board(attribute: &str, options: &RunOpts) -> anyhow::Result<Board> {
+    let config = tw::config(options)?;
+    let attributes = groupable_attributes(&config);
+    if !attributes.iter().any(|a| a == attribute) {
+        return Err(anyhow::anyhow!("Can not group tasks by {}", attribute));
+    }
+
+    let tasks = if attribute == "status" {
+        let completed_min_end = std::time::SystemTime::now()
+            .checked_sub(COMPLETED_MAX_AGE)
+            .unwrap()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        let end_filter = format!("end.after:{}", completed_min_end);
+        tw::export(
+            &[
+                "(",
+                "status:pending",
+                "or",
+                "status:waiting",
+                "or",
+                "(",
+                "status:completed",
+                &end_filter,
+                ")",
+                ")",
+            ],
+            options,
+        )?
+    } else {
+        tw::export(&["status:pending"], options)?
+    };
+
+    // Build columns from allowed values, then add any other value found in tasks
+    let mut values: Vec<String> = if attribute == "status" {
+        STATUS_VALUES.iter().map(|v| v.to_string()).collect()
+    } else {
+        attribute_values(attribute, &config).unwrap_or_else(|| vec!["".to_string()])
+    };
+    let mut other_values: Vec<String> = tasks
+        .iter()
+        .map(|t| task_value(t, attribute))
+        .filter(|v| !values.contains(v))
+        .collect();
+    other_values.sort();
+    other_values.dedup();
+    values.extend(other_values);
+
+    let mut columns: Vec<BoardColumn> = values
+        .into_iter()
+        .map(|value| BoardColumn {
+            value,
+            tasks: vec![],
+        })
+        .collect();
+    for task in tasks {
+        let value = task_value(&task, attribute);
+        if let Some(column) = columns.iter_mut().find(|c| c.value == value) {
+            column.tasks.push(task);
+        }
+    }
+    for column in &mut columns {
+        column
+            .tasks
+            .sort_by(|a, b| b.urgency.partial_cmp(&a.urgency).unwrap());
+    }
+
+    Ok(Board {
+        attribute: attribute.to_string(),
+        attributes,
+        columns,
+    })
+}
+
+/// Get task arguments to move a task to a board column
+pub fn move_args(uuid: &str, attribute: &str, value: &str) -> anyhow::Result<Vec<String>> {
+    let modification = format!("{}:{}", attribute, value);
+    let args: Vec<&str> = match (attribute, value) {
+        ("status", "pending") => vec!["modify", "status:pending", "start:", "wait:"],
+        ("status", "active") => vec!["start"],
+        ("status", "waiting") => vec!["modify", "wait:someday"],
+        ("status", "completed") => vec!["done"],
+        ("status", _) => return Err(anyhow::anyhow!("Invalid status {}", value)),
+        _ => vec!["modify", &modification],
+    };
+
+    Ok(std::iter::once(uuid)
+        .chain(args)
+        .map(str::to_string)
+        .collect())
+}
@@ -0,0 +1,141 @@
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkAction {
+    Done,
+    Delete,
+    /// Arbitrary modifications, like `priority:H +next`
+    Modify,
+    Tag,
+    Untag,
+    Project,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct BulkRequest {
+    uuids: Vec<String>,
+    action: BulkAction,
+    #[serde(default)]
+    value: String,
+    /// Only return what would be done
+    #[serde(default)]
+    preview: bool,
+    /// Modification time of each task when displayed, RFC 3339 formatted
+    #[serde(default)]
+    modified: std::collections::HashMap<String, String>,
+}
+
+impl BulkRequest {
+    /// Selected tasks with their expected modification time, for conflict detection
+    pub fn unmodified_since(&self) -> anyhow::Result<Vec<(&str, tw::DateTime)>> {
+        self.uuids
+            .iter()
+            .filter_map(|uuid| Some((uuid, self.modified.get(uuid)?)))
+            .map(|(uuid, modified)| {
+                let modified = chrono::DateTime::parse_from_rfc3339(modified)?;
+                Ok((uuid.as_str(), modified.with_timezone(&chrono::Utc)))
+            })
+            .collect()
+    }
+}
+
+#[derive(serde::Serialize)]
+struct BulkTaskResult {
+    uuid: String,
+    description: String,
+    status: String,
+    changed: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct BulkResult {
+    dry_run: bool,
+    command: Vec<String>,
+    code: i32,
+    output: String,
+    tasks: Vec<BulkTaskResult>,
+}
+
+/// Build arguments to run action on all tasks at once, without confirmation
+fn command_args(request: &BulkRequest) -> anyhow::Result<Vec<String>> {
+    if request.uuids.is_empty() {
+        return Err(anyhow::anyhow!("No task selected"));
+    }
+    if let Some(uuid) = request.uuids.iter().find(|u| !tw::is_uuid(u)) {
+        return Err(anyhow::anyhow!("Invalid UUID {:?}", uuid));
+    }
+    let value = request.value.trim();
+    let needs_value = !matches!(request.action, BulkAction::Done | BulkAction::Delete);
+    if needs_value && value.is_empty() {
+        return Err(anyhow::anyhow!("Missing value for {:?}", request.action));
+    }
+
+    let mut args = vec!["rc.bulk:0".to_string(), "rc.confirmation:off".to_string()];
+    args.extend(request.uuids.iter().cloned());
+    match request.action {
+        BulkAction::Done => args.push("done".to_string()),
+        BulkAction::Delete => args.push("delete".to_string()),
+        BulkAction::Modify => {
+            args.push("modify".to_string());
+            args.extend(shell_words::split(value)?);
+        }
+        BulkAction::Tag => {
+            args.push("modify".to_string());
+            args.push(format!("+{}", value));
+        }
+        BulkAction::Untag => {
+            args.push("modify".to_string());
+            args.push(format!("-{}", value));
+        }
+        BulkAction::Project => {
+            args.push("modify".to_string());
+            args.push(format!("project:{}", value));
+        }
+    }
+    Ok(args)
+}
+
+pub fn run(request: &BulkRequest, options: &RunOpts) -> anyhow::Result<BulkResult> {
+    let args = command_args(request)?;
+    let uuid_filter: Vec<&str> = request.uuids.iter().map(String::as_str).collect();
+    let tasks_before = tw::export(&uuid_filter, options)?;
+
+    let dry_run = options.dry_run || request.preview;
+    let (code, output) = if dry_run {
+        (0, String::new())
+    } else {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        tw::invoke_external(&args, options)?
+    };
+
+    let tasks_after = if dry_run {
+        tasks_before.clone()
+    } else {
+        tw::export(&uuid_filter, options)?
+    };
+    let tasks = tasks_after
+        .into_iter()
+        .map(|after| {
+            let changed = match tasks_before.iter().find(|b| b.uuid == after.uuid) {
+                Some(before) => *before != after,
+                None => true,
+            };
+            BulkTaskResult {
+                uuid: after.uuid,
+                description: after.description,
+                status: after.status,
+                changed,
+            }
+        })
+        .collect();
+
+    Ok(BulkResult {
+        dry_run,
+        command: std::iter::once("task".to_string()).chain(args).collect(),
+        code,
+        output,
+        tasks,
+    })
+}
@@ -0,0 +1,125 @@
+use chrono::Datelike;
+
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+#[derive(serde::Serialize)]
+struct CalendarTask {
+    uuid: String,
+    description: String,
+    kind: &'static str,
+    overdue: bool,
+}
+
+#[derive(serde::Serialize)]
+struct CalendarDay {
+    date: String,
+    day: u32,
+    in_month: bool,
+    today: bool,
+    tasks: Vec<CalendarTask>,
+}
+
+#[derive(serde::Serialize)]
+pub struct Calendar {
+    month: String,
+    prev_month: String,
+    next_month: String,
+    weekdays: Vec<String>,
+    weeks: Vec<Vec<CalendarDay>>,
+}
+
+static MONTH_FORMAT: &str = "%Y-%m";
+
+fn parse_month(month: &str) -> anyhow::Result<chrono::NaiveDate> {
+    Ok(chrono::NaiveDate::parse_from_str(
+        &format!("{}-01", month),
+        "%Y-%m-%d",
+    )?)
+}
+
+pub fn add_months(first_day: chrono::NaiveDate, months: i32) -> chrono::NaiveDate {
+    let month0 = first_day.year() * 12 + first_day.month0() as i32 + months;
+    chrono::NaiveDate::from_ymd_opt(month0.div_euclid(12), month0.rem_euclid(12) as u32 + 1, 1)
+        .unwrap()
+}
+
+pub fn calendar(month: Option<&str>, options: &RunOpts) -> anyhow::Result<Calendar> {
+    let today = chrono::Local::now().naive_local().date();
+    let first_day = match month {
+        Some(month) => parse_month(month)?,
+        None => chrono::NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap(),
+    };
+    let next_first_day = add_months(first_day, 1);
+
+    // Start grid at the first day of the week containing the first day of month
+    let config = tw::config(options)?;
+    let week_offset = match config.get("weekstart").map(String::as_str) {
+        Some("monday") | Some("Monday") => first_day.weekday().num_days_from_monday(),
+        _ => first_day.weekday().num_days_from_sunday(),
+    };
+    let grid_start = first_day - chrono::Duration::days(week_offset.into());
+
+    let mut weeks = Vec::new();
+    let mut date = grid_start;
+    while date < next_first_day {
+        let mut week = Vec::new();
+        for _ in 0..7 {
+            week.push(CalendarDay {
+                date: date.format("%Y-%m-%d").to_string(),
+                day: date.day(),
+                in_month: (date >= first_day) && (date < next_first_day),
+                today: date == today,
+                tasks: vec![],
+            });
+            date += chrono::Duration::days(1);
+        }
+        weeks.push(week);
+    }
+    let weekdays = (0..7)
+        .map(|i| {
+            (grid_start + chrono::Duration::days(i))
+                .format("%a")
+                .to_string()
+        })
+        .collect();
+
+    // Place tasks on the grid
+    let now = chrono::Utc::now();
+    let tasks = tw::export(
+        &["(", "status:pending", "or", "status:waiting", ")"],
+        options,
+    )?;
+    for task in tasks {
+        for (kind, task_date) in &[
+            ("due", &task.due),
+            ("scheduled", &task.scheduled),
+            ("wait", &task.wait),
+        ] {
+            let task_date = match task_date {
+                Some(task_date) => task_date,
+                None => continue,
+            };
+            let days = (tw::local_date(task_date) - grid_start).num_days();
+            if days < 0 || days >= 7 * weeks.len() as i64 {
+                continue;
+            }
+            weeks[days as usize / 7][days as usize % 7]
+                .tasks
+                .push(CalendarTask {
+                    uuid: task.uuid.clone(),
+                    description: task.description.clone(),
+                    kind: *kind,
+                    overdue: (*kind == "due") && (*task_date < now),
+                });
+        }
+    }
+
+    Ok(Calendar {
+        month: first_day.format(MONTH_FORMAT).to_string(),
+        prev_month: add_months(first_day, -1).format(MONTH_FORMAT).to_string(),
+        next_month: next_first_day.format(MONTH_FORMAT).to_string(),
+        weekdays,
+        weeks,
+    })
+}
@@ -0,0 +1,279 @@
+use std::fmt::Write;
+
+use chrono::Datelike;
+
+use crate::tw;
+
+#[derive(Clone, Copy, Debug, strum_macros::EnumString)]
+pub enum Period {
+    #[strum(serialize = "daily")]
+    Daily,
+    #[strum(serialize = "weekly")]
+    Weekly,
+    #[strum(serialize = "monthly")]
+    Monthly,
+}
+
+impl Period {
+    /// Get first day of the period containing a date
+    fn start(self, date: chrono::NaiveDate) -> chrono::NaiveDate {
+        match self {
+            Period::Daily => date,
+            Period::Weekly => {
+                date - chrono::Duration::days(date.weekday().num_days_from_monday().into())
+            }
+            Period::Monthly => date.with_day(1).unwrap(),
+        }
+    }
+
+    /// Get first day of the next period
+    fn next(self, start: chrono::NaiveDate) -> chrono::NaiveDate {
+        match self {
+            Period::Daily => start + chrono::Duration::days(1),
+            Period::Weekly => start + chrono::Duration::days(7),
+            Period::Monthly => crate::calendar::add_months(start, 1),
+        }
+    }
+
+    fn label(self, start: chrono::NaiveDate) -> String {
+        match self {
+            Period::Daily => start.format("%Y-%m-%d"),
+            Period::Weekly => start.format("%G-W%V"),
+            Period::Monthly => start.format("%Y-%m"),
+        }
+        .to_string()
+    }
+}
+
+/// Task counts for a period
+pub struct PeriodCounts {
+    label: String,
+    added: u32,
+    completed: u32,
+    deleted: u32,
+}
+
+/// Count added, completed and deleted tasks per period, from the first task entry until today
+pub fn history(tasks: &[tw::ExportedTask], period: Period) -> Vec<PeriodCounts> {
+    let today = chrono::Local::now().naive_local().date();
+    let first_day = match tasks
+        .iter()
+        .filter_map(|t| t.entry.as_ref())
+        .map(tw::local_date)
+        .min()
+    {
+        Some(first_day) => period.start(first_day),
+        None => return vec![],
+    };
+
+    let mut starts = Vec::new();
+    let mut start = first_day;
+    while start <= today {
+        starts.push(start);
+        start = period.next(start);
+    }
+    if starts.is_empty() {
+        // All tasks entered after today
+        return vec![];
+    }
+    let mut r: Vec<PeriodCounts> = starts
+        .iter()
+        .map(|s| PeriodCounts {
+            label: period.label(*s),
+            added: 0,
+            completed: 0,
+            deleted: 0,
+        })
+        .collect();
+    let index = |date: &tw::DateTime| -> usize {
+        let i = match starts.binary_search(&tw::local_date(date)) {
+            Ok(i) => i,
+            Err(i) => std::cmp::max(i, 1) - 1,
+        };
+        std::cmp::min(i, starts.len() - 1)
+    };
+
+    for task in tasks {
+        if task.status == "recurring" {
+            continue;
+        }
+        if let Some(entry) = &task.entry {
+            r[index(entry)].added += 1;
+        }
+        if let Some(end) = &task.end {
+            match task.status.as_str() {
+                "completed" => r[index(end)].completed += 1,
+                "deleted" => r[index(end)].deleted += 1,
+                _ => {}
+            }
+        }
+    }
+
+    r
+}
+
+const CHART_HEIGHT: u32 = 300;
+const CHART_MARGIN: u32 = 40;
+const BAR_WIDTH: u32 = 8;
+const BAR_GAP: u32 = 4;
+const LABEL_MIN_SPACING: u32 = 80;
+
+const COLOR_ADDED: &str = "#d9534f";
+const COLOR_COMPLETED: &str = "#5cb85c";
+const COLOR_DELETED: &str = "#999999";
+const COLOR_PENDING: &str = "#f0ad4e";
+
+/// Draw stacked or grouped bars for each period, and return SVG document
+fn bar_chart(labels: &[&str], series: &[(&str, &str, Vec<u32>)], stacked: bool) -> String {
+    let bars_per_group = if stacked { 1 } else { series.len() as u32 };
+    let group_width = bars_per_group * BAR_WIDTH + BAR_GAP;
+    let width = 2 * CHART_MARGIN + std::cmp::max(1, labels.len() as u32) * group_width;
+    let height = CHART_HEIGHT + 2 * CHART_MARGIN;
+    let max_value = (0..labels.len())
+        .map(|i| {
+            let values = series.iter().map(|s| s.2[i]);
+            if stacked {
+                values.sum::<u32>()
+            } else {
+                values.max().unwrap_or(0)
+            }
+        })
+        .max()
+        .unwrap_or(0);
+    let scale = |v: u32| -> u32 { v * CHART_HEIGHT / std::cmp::max(1, max_value) };
+
+    let mut r = String::new();
+    writeln!(
+        r,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+        width, height, width, height
+    )
+    .unwrap();
+
+    // Axes
+    let bottom = CHART_MARGIN + CHART_HEIGHT;
+    writeln!(
+        r,
+        "<line x1=\"{m}\" y1=\"{m}\" x2=\"{m}\" y2=\"{b}\" stroke=\"currentColor\"/>\
+         <line x1=\"{m}\" y1=\"{b}\" x2=\"{w}\" y2=\"{b}\" stroke=\"currentColor\"/>",
+        m = CHART_MARGIN,
+        b = bottom,
+        w = width - CHART_MARGIN
+    )
+    .unwrap();
+    writeln!(
+        r,
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" font-size=\"10\" fill=\"currentColor\">{}</text>",
+        CHART_MARGIN - 4,
+        CHART_MARGIN + 4,
+        max_value
+    )
+    .unwrap();
+    writeln!(
+        r,
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" font-size=\"10\" fill=\"currentColor\">0</text>",
+        CHART_MARGIN - 4,
+        bottom + 4
+    )
+    .unwrap();
+
+    // Legend
+    for (i, (name, color, _)) in series.iter().enumerate() {
+        let x = CHART_MARGIN + i as u32 * LABEL_MIN_SPACING;
+        writeln!(
+            r,
+            "<rect x=\"{}\" y=\"{}\" width=\"10\" height=\"10\" fill=\"{}\"/>\
+             <text x=\"{}\" y=\"{}\" font-size=\"10\" fill=\"currentColor\">{}</text>",
+            x,
+            CHART_MARGIN / 2 - 8,
+            color,
+            x + 14,
+            CHART_MARGIN / 2,
+            name
+        )
+        .unwrap();
+    }
+
+    // Bars
+    let label_step = std::cmp::max(1, LABEL_MIN_SPACING / group_width) as usize;
+    for (i, label) in labels.iter().enumerate() {
+        let group_x = CHART_MARGIN + i as u32 * group_width + BAR_GAP / 2;
+        let mut stack_height = 0;
+        for (j, (name, color, values)) in series.iter().enumerate() {
+            let bar_height = scale(values[i]);
+            let (x, y) = if stacked {
+                stack_height += bar_height;
+                (group_x, bottom - stack_height)
+            } else {
+                (group_x + j as u32 * BAR_WIDTH, bottom - bar_height)
+            };
+            writeln!(
+                r,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"><title>{} {}: {}</title></rect>",
+                x, y, BAR_WIDTH, bar_height, color, label, name, values[i]
+            )
+            .unwrap();
+        }
+        if i % label_step == 0 {
+            writeln!(
+                r,
+                "<text x=\"{}\" y=\"{}\" font-size=\"10\" fill=\"currentColor\">{}</text>",
+                group_x,
+                bottom + 14,
+                label
+            )
+            .unwrap();
+        }
+    }
+
+    r.push_str("</svg>");
+    r
+}
+
+/// Render added, completed and deleted task counts per period as SVG
+pub fn history_svg(history: &[PeriodCounts]) -> String {
+    let labels: Vec<&str> = history.iter().map(|p| p.label.as_str()).collect();
+    bar_chart(
+        &labels,
+        &[
+            (
+                "added",
+                COLOR_ADDED,
+                history.iter().map(|p| p.added).collect(),
+            ),
+            (
+                "completed",
+                COLOR_COMPLETED,
+                history.iter().map(|p| p.completed).collect(),
+            ),
+            (
+                "deleted",
+                COLOR_DELETED,
+                history.iter().map(|p| p.deleted).collect(),
+            ),
+        ],
+        false,
+    )
+}
+
+/// Render pending and done task counts at the end of each period as SVG
+pub fn burndown_svg(history: &[PeriodCounts]) -> String {
+    let labels: Vec<&str> = history.iter().map(|p| p.label.as_str()).collect();
+    let mut pending = Vec::new();
+    let mut done = Vec::new();
+    let (mut cur_pending, mut cur_done): (u32, u32) = (0, 0);
+    for p in history {
+        cur_done += p.completed;
+        cur_pending = (cur_pending + p.added).saturating_sub(p.completed + p.deleted);
+        pending.push(cur_pending);
+        done.push(cur_done);
+    }
+    bar_chart(
+        &labels,
+        &[
+            ("pending", COLOR_PENDING, pending),
+            ("done", COLOR_COMPLETED, done),
+        ],
+        true,
+    )
+}
@@ -0,0 +1,339 @@
+use crate::tw;
+
+/// Default of `rule.precedence.color`, highest precedence first
+static DEFAULT_PRECEDENCE: &str = "deleted,completed,active,keyword.,tag.,project.,overdue,scheduled,due.today,due,blocked,blocking,recurring,tagged,uda.";
+
+/// RGB values of the 16 basic terminal colors
+static BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0x80, 0x00, 0x00),
+    (0x00, 0x80, 0x00),
+    (0x80, 0x80, 0x00),
+    (0x00, 0x00, 0x80),
+    (0x80, 0x00, 0x80),
+    (0x00, 0x80, 0x80),
+    (0xc0, 0xc0, 0xc0),
+    (0x80, 0x80, 0x80),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x00, 0x00, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+static BASIC_COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// Levels of each component in the 6x6x6 color cube
+static CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Terminal color, as set by a `color.<rule>` configuration entry
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Color {
+    /// 256 color palette indexes
+    fg: Option<u8>,
+    bg: Option<u8>,
+    bold: bool,
+    underline: bool,
+    inverse: bool,
+}
+
+fn palette_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC_COLORS[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Approximate luminance, from 0 (black) to 1 (white)
+fn luminance(index: u8) -> f64 {
+    let (r, g, b) = palette_rgb(index);
+    (0.2126 * f64::from(r) + 0.7152 * f64::from(g) + 0.0722 * f64::from(b)) / 255.0
+}
+
+fn css_color(index: u8) -> String {
+    let (r, g, b) = palette_rgb(index);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Parse a single color word, `red`, `color9`, `rgb500` or `gray12`
+fn parse_color_word(word: &str, bright: bool) -> Option<u8> {
+    if let Some(i) = BASIC_COLOR_NAMES.iter().position(|n| *n == word) {
+        return Some(i as u8 + if bright { 8 } else { 0 });
+    }
+    if let Some(n) = word.strip_prefix("color") {
+        return n.parse().ok();
+    }
+    if let Some(rgb) = word.strip_prefix("rgb") {
+        let levels: Vec<u8> = rgb
+            .chars()
+            .map(|c| c.to_digit(10).filter(|d| *d < 6).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        return match levels.as_slice() {
+            [r, g, b] => Some(16 + 36 * r + 6 * g + b),
+            _ => None,
+        };
+    }
+    if let Some(n) = word
+        .strip_prefix("gray")
+        .or_else(|| word.strip_prefix("grey"))
+    {
+        return n.parse::<u8>().ok().filter(|n| *n < 24).map(|n| 232 + n);
+    }
+    None
+}
+
+impl std::str::FromStr for Color {
+    type Err = anyhow::Error;
+
+    /// Parse taskwarrior color syntax, like `bold white on rgb500`
+    fn from_str(s: &str) -> anyhow::Result<Color> {
+        let mut color = Color::default();
+        let mut background = false;
+        let mut bright = false;
+        for word in s.split_whitespace().map(str::to_lowercase) {
+            match word.as_str() {
+                "on" => background = true,
+                "bright" => bright = true,
+                "bold" => color.bold = true,
+                "underline" => color.underline = true,
+                "inverse" => color.inverse = true,
+                _ => {
+                    let index = parse_color_word(&word, bright)
+                        .ok_or_else(|| anyhow::anyhow!("Invalid color {:?} in {:?}", word, s))?;
+                    if background {
+                        color.bg = Some(index);
+                    } else {
+                        color.fg = Some(index);
+                    }
+                    bright = false;
+                }
+            }
+        }
+        Ok(color)
+    }
+}
+
+impl Color {
+    /// Apply a color of higher precedence, which overrides colors it sets
+    fn blend(&mut self, other: &Color) {
+        self.fg = other.fg.or(self.fg);
+        self.bg = other.bg.or(self.bg);
+        self.bold |= other.bold;
+        self.underline |= other.underline;
+        self.inverse |= other.inverse;
+    }
+
+    /// CSS custom properties, applied to rows by theme.css so that themes can remap them
+    ///
+    /// Terminal colors are chosen for a terminal background, so foreground colors are only set for
+    /// light or dark pages where they are readable, and colors on a background always are.
+    pub fn style(&self) -> String {
+        let (fg, bg) = if self.inverse {
+            (self.bg.or(Some(0)), self.fg.or(Some(7)))
+        } else {
+            (self.fg, self.bg)
+        };
+        let mut style = Vec::new();
+        match (fg, bg) {
+            (fg, Some(bg)) => {
+                let fg = fg.unwrap_or(if luminance(bg) > 0.5 { 0 } else { 15 });
+                style.push(format!("--task-fg-light: {}", css_color(fg)));
+                style.push(format!("--task-fg-dark: {}", css_color(fg)));
+                style.push(format!("--task-bg: {}", css_color(bg)));
+            }
+            (Some(fg), None) => {
+                if luminance(fg) < 0.6 {
+                    style.push(format!("--task-fg-light: {}", css_color(fg)));
+                }
+                if luminance(fg) > 0.4 {
+                    style.push(format!("--task-fg-dark: {}", css_color(fg)));
+                }
+            }
+            (None, None) => {}
+        }
+        if self.bold {
+            style.push("--task-font-weight: bold".to_string());
+        }
+        if self.underline {
+            style.push("--task-text-decoration: underline".to_string());
+        }
+        style.join("; ")
+    }
+}
+
+/// Tasks of a report matching rules that depend on other tasks
+///
+/// Derived from the `depends` attribute of exported tasks, so only dependencies between tasks of the
+/// report are considered.
+#[derive(Default)]
+pub struct Dependencies {
+    blocked: std::collections::HashSet<String>,
+    blocking: std::collections::HashSet<String>,
+}
+
+impl Dependencies {
+    pub fn new<'a>(tasks: impl Iterator<Item = &'a tw::ExportedTask> + Clone) -> Dependencies {
+        let pending: std::collections::HashSet<&str> = tasks
+            .clone()
+            .filter(|t| t.status == "pending" || t.status == "waiting")
+            .map(|t| t.uuid.as_str())
+            .collect();
+        let mut r = Dependencies::default();
+        for task in tasks {
+            for dependency in &task.depends {
+                if pending.contains(dependency.as_str()) {
+                    r.blocked.insert(task.uuid.clone());
+                }
+                if pending.contains(task.uuid.as_str()) {
+                    r.blocking.insert(dependency.clone());
+                }
+            }
+        }
+        r
+    }
+}
+
+/// Color rules from configuration
+pub struct Rules {
+    /// Master `color` setting, no rule matches when off
+    enabled: bool,
+    /// Rules or rule prefixes, highest precedence first
+    precedence: Vec<String>,
+    /// Colors of `color.<rule>` entries, by rule
+    colors: std::collections::HashMap<String, Color>,
+    /// Number of days a task is considered due before its due date
+    due_days: i64,
+}
+
+impl Rules {
+    pub fn new(config: &std::collections::HashMap<String, String>) -> Rules {
+        let colors = config
+            .iter()
+            .filter_map(|(k, v)| Some((k.strip_prefix("color.")?, v)))
+            .filter(|(_, v)| !v.trim().is_empty())
+            .filter_map(|(rule, v)| match v.parse::<Color>() {
+                Ok(color) => Some((rule.to_string(), color)),
+                Err(e) => {
+                    log::warn!("Ignoring color.{}: {}", rule, e);
+                    None
+                }
+            })
+            .collect();
+        Rules {
+            enabled: config.get("color").map_or(true, |c| {
+                ["on", "yes", "y", "1", "true"].contains(&c.trim())
+            }),
+            precedence: config
+                .get("rule.precedence.color")
+                .map_or(DEFAULT_PRECEDENCE, String::as_str)
+                .split(',')
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty())
+                .collect(),
+            colors,
+            due_days: config.get("due").and_then(|d| d.parse().ok()).unwrap_or(7),
+        }
+    }
+
+    /// Whether a rule without parameter matches task
+    fn matches(&self, rule: &str, task: &tw::ExportedTask, dependencies: &Dependencies) -> bool {
+        let pending = task.status == "pending" || task.status == "waiting";
+        let today = chrono::Local::today();
+        let due_date = task.due.map(|d| d.with_timezone(&chrono::Local).date());
+        match rule {
+            "active" => pending && task.start.is_some(),
+            "blocked" => dependencies.blocked.contains(&task.uuid),
+            "blocking" => dependencies.blocking.contains(&task.uuid),
+            "completed" => task.status == "completed",
+            "deleted" => task.status == "deleted",
+            "due" => {
+                pending
+                    && due_date.map_or(false, |d| {
+                        d >= today && d <= today + chrono::Duration::days(self.due_days)
+                    })
+            }
+            "due.today" => pending && due_date == Some(today),
+            "overdue" => pending && task.due.map_or(false, |d| d < chrono::Utc::now()),
+            "recurring" => task.attribute("recur").is_some(),
+            "scheduled" => task.scheduled.is_some(),
+            "tagged" => !task.tags.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Whether a parameterized rule like `tag.next` matches task
+    fn matches_parameterized(&self, rule: &str, task: &tw::ExportedTask) -> bool {
+        let (kind, parameter) = match rule.split_once('.') {
+            Some(s) => s,
+            None => return false,
+        };
+        match kind {
+            "keyword" => task.description.contains(parameter),
+            "project" if parameter == "none" => task.project.is_none(),
+            "project" => task.project.as_ref().map_or(false, |p| {
+                p == parameter || p.starts_with(&format!("{}.", parameter))
+            }),
+            "tag" if parameter == "none" => task.tags.is_empty(),
+            "tag" => task.tags.iter().any(|t| t == parameter),
+            "uda" => match parameter.split_once('.') {
+                Some((uda, "none")) => task.attribute(uda).is_none(),
+                Some((uda, value)) => task.attribute(uda).map_or(false, |v| v == value),
+                None => task.attribute(parameter).is_some(),
+            },
+            _ => false,
+        }
+    }
+
+    /// Rules matching a task, highest precedence first
+    pub fn matching(&self, task: &tw::ExportedTask, dependencies: &Dependencies) -> Vec<String> {
+        let mut matching = Vec::new();
+        if !self.enabled {
+            return matching;
+        }
+        for rule in &self.precedence {
+            if rule.ends_with('.') {
+                let mut configured: Vec<&String> = self
+                    .colors
+                    .keys()
+                    .filter(|r| r.starts_with(rule.as_str()))
+                    .collect();
+                configured.sort();
+                matching.extend(
+                    configured
+                        .into_iter()
+                        .filter(|r| self.matches_parameterized(r, task))
+                        .cloned(),
+                );
+            } else if self.matches(rule, task, dependencies) {
+                matching.push(rule.clone());
+            }
+        }
+        matching
+    }
+
+    /// Style blending colors of matching rules, lowest precedence first
+    pub fn style(&self, matching: &[String]) -> Option<String> {
+        let mut color = Color::default();
+        for rule in matching.iter().rev() {
+            if let Some(rule_color) = self.colors.get(rule) {
+                color.blend(rule_color);
+            }
+        }
+        Some(color.style()).filter(|s| !s.is_empty())
+    }
+}
@@ -0,0 +1,87 @@
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+/// `If-Unmodified-Since` header, sent with edits of a previously displayed task
+pub struct IfUnmodifiedSince(pub Option<tw::DateTime>);
+
+impl<'a, 'r> rocket::request::FromRequest<'a, 'r> for IfUnmodifiedSince {
+    type Error = ();
+
+    fn from_request(
+        request: &'a rocket::request::Request<'r>,
+    ) -> rocket::request::Outcome<Self, Self::Error> {
+        match request.headers().get_one("If-Unmodified-Since") {
+            Some(since) => match httpdate::parse_http_date(since) {
+                Ok(since) => {
+                    rocket::request::Outcome::Success(IfUnmodifiedSince(Some(since.into())))
+                }
+                Err(_) => rocket::request::Outcome::Failure((rocket::http::Status::BadRequest, ())),
+            },
+            None => rocket::request::Outcome::Success(IfUnmodifiedSince(None)),
+        }
+    }
+}
+
+impl IfUnmodifiedSince {
+    /// Check a single task, if the header was sent
+    pub fn check(
+        &self,
+        uuid: &str,
+        options: &RunOpts,
+    ) -> anyhow::Result<Result<(), ConflictResponse>> {
+        match self.0 {
+            Some(since) => check(&[(uuid, since)], options),
+            None => Ok(Ok(())),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct Conflict {
+    uuid: String,
+    description: String,
+    modified: Option<String>,
+    /// Changes made since the task was displayed
+    changes: Vec<crate::undo::AttributeChange>,
+}
+
+pub type ConflictResponse =
+    rocket::response::status::Custom<rocket_contrib::json::Json<Vec<Conflict>>>;
+
+/// Check that tasks were not modified since they were displayed
+pub fn check(
+    expected: &[(&str, tw::DateTime)],
+    options: &RunOpts,
+) -> anyhow::Result<Result<(), ConflictResponse>> {
+    if expected.is_empty() {
+        return Ok(Ok(()));
+    }
+    let uuids: Vec<&str> = expected.iter().map(|(uuid, _)| *uuid).collect();
+    let tasks = tw::export(&uuids, options)?;
+
+    let mut conflicts = Vec::new();
+    for (uuid, since) in expected {
+        let task = tasks
+            .iter()
+            .find(|t| t.uuid == *uuid)
+            .ok_or_else(|| anyhow::anyhow!("No task with UUID {}", uuid))?;
+        match task.modified {
+            Some(modified) if modified > *since => conflicts.push(Conflict {
+                uuid: task.uuid.clone(),
+                description: task.description.clone(),
+                modified: Some(crate::dates::timestamp(&modified)),
+                changes: crate::undo::changes_since(uuid, since, options)?,
+            }),
+            _ => {}
+        }
+    }
+
+    if conflicts.is_empty() {
+        Ok(Ok(()))
+    } else {
+        Ok(Err(rocket::response::status::Custom(
+            rocket::http::Status::Conflict,
+            rocket_contrib::json::Json(conflicts),
+        )))
+    }
+}
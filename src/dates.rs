@@ -0,0 +1,33 @@
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+/// ISO 8601 formats written by taskwarrior, basic in export and extended elsewhere
+static ISO_FORMATS: [&str; 2] = ["%Y%m%dT%H%M%SZ", "%Y-%m-%dT%H:%M:%SZ"];
+
+/// Local date format of `task calc` output, see `tw::calc`
+static CALC_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Parse an UTC timestamp as written by taskwarrior
+pub fn parse_iso(s: &str) -> anyhow::Result<tw::DateTime> {
+    ISO_FORMATS
+        .iter()
+        .find_map(|f| chrono::NaiveDateTime::parse_from_str(s, f).ok())
+        .map(|naive| chrono::TimeZone::from_utc_datetime(&chrono::Utc, &naive))
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp {:?}", s))
+}
+
+/// Resolve a date, possibly a synonym like `eom` or `monday`, with taskwarrior
+pub fn resolve(expression: &str, options: &RunOpts) -> anyhow::Result<tw::DateTime> {
+    let resolved = tw::calc(expression, options)?;
+    let naive = chrono::NaiveDateTime::parse_from_str(&resolved, CALC_FORMAT)
+        .map_err(|_| anyhow::anyhow!("Invalid date {:?}", expression))?;
+    chrono::TimeZone::from_local_datetime(&chrono::Local, &naive)
+        .earliest()
+        .map(|d| d.with_timezone(&chrono::Utc))
+        .ok_or_else(|| anyhow::anyhow!("Nonexistent local date {:?}", expression))
+}
+
+/// Format a date for clients, as RFC 3339 in UTC
+pub fn timestamp(date: &tw::DateTime) -> String {
+    date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
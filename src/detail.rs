@@ -0,0 +1,170 @@
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+/// Attributes displayed first, in this order
+static ATTRIBUTE_ORDER: [&str; 16] = [
+    "description",
+    "status",
+    "project",
+    "tags",
+    "priority",
+    "due",
+    "scheduled",
+    "wait",
+    "until",
+    "recur",
+    "start",
+    "end",
+    "entry",
+    "modified",
+    "urgency",
+    "uuid",
+];
+
+/// Attributes displayed separately
+static ATTRIBUTE_SKIP: [&str; 3] = ["annotations", "depends", "parent"];
+
+/// Format a date in local timezone, in a format taskwarrior can parse back
+pub fn format_local_date(date: &tw::DateTime) -> String {
+    date.with_timezone(&chrono::Local)
+        .format("%Y-%m-%dT%H:%M:%S")
+        .to_string()
+}
+
+#[derive(serde::Serialize)]
+struct TaskAttribute {
+    name: String,
+    value: String,
+    column_type: tw::ColumnType,
+}
+
+#[derive(serde::Serialize)]
+struct LinkedTask {
+    uuid: String,
+    description: String,
+    status: String,
+}
+
+impl From<&tw::ExportedTask> for LinkedTask {
+    fn from(task: &tw::ExportedTask) -> Self {
+        LinkedTask {
+            uuid: task.uuid.clone(),
+            description: task.description.clone(),
+            status: task.status.clone(),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct TaskDetail {
+    uuid: String,
+    pub description: String,
+    modified: Option<String>,
+    attributes: Vec<TaskAttribute>,
+    annotations: Vec<tw::Annotation>,
+    depends: Vec<LinkedTask>,
+    blocks: Vec<LinkedTask>,
+    parent: Option<LinkedTask>,
+    children: Vec<LinkedTask>,
+    history: Vec<crate::undo::HistoryEntry>,
+    /// History can not be read from taskwarrior 2.6+ storage
+    history_unsupported: bool,
+}
+
+fn attribute_value(task: &tw::ExportedTask, name: &str) -> Option<String> {
+    let value = task.attribute(name)?;
+    match tw::column_type(name) {
+        Some(tw::ColumnType {
+            type_: tw::AttributeType::DateTime,
+            ..
+        }) => Some(
+            tw::parse_date(&value)
+                .map(|d| format_local_date(&d))
+                .unwrap_or(value),
+        ),
+        _ => Some(value),
+    }
+}
+
+fn linked_tasks(filter: &[&str], options: &RunOpts) -> anyhow::Result<Vec<LinkedTask>> {
+    Ok(tw::export(filter, options)?
+        .iter()
+        .map(LinkedTask::from)
+        .collect())
+}
+
+pub fn task_detail(uuid: &str, options: &RunOpts) -> anyhow::Result<TaskDetail> {
+    let task = tw::export(&[uuid], options)?
+        .into_iter()
+        .find(|t| t.uuid == uuid)
+        .ok_or_else(|| anyhow::anyhow!("No task with UUID {}", uuid))?;
+
+    // Attributes
+    let mut names: Vec<String> = ATTRIBUTE_ORDER.iter().map(|n| n.to_string()).collect();
+    let mut other_names: Vec<String> = task
+        .other
+        .keys()
+        .filter(|n| !ATTRIBUTE_ORDER.contains(&n.as_str()))
+        .filter(|n| !ATTRIBUTE_SKIP.contains(&n.as_str()))
+        .cloned()
+        .collect();
+    other_names.sort();
+    names.extend(other_names);
+    let attributes = names
+        .into_iter()
+        .filter_map(|name| {
+            let value = attribute_value(&task, &name)?;
+            let column_type = tw::column_type(&name).unwrap_or(tw::ColumnType {
+                type_: tw::AttributeType::String,
+                read_only: true,
+            });
+            Some(TaskAttribute {
+                name,
+                value,
+                column_type,
+            })
+        })
+        .collect();
+
+    // Dependencies in both directions
+    let depends = if task.depends.is_empty() {
+        vec![]
+    } else {
+        let depends_filter: Vec<&str> = task.depends.iter().map(String::as_str).collect();
+        linked_tasks(&depends_filter, options)?
+    };
+    let blocks = tw::export(&["depends.any:"], options)?
+        .iter()
+        .filter(|t| t.depends.iter().any(|d| d == uuid))
+        .map(LinkedTask::from)
+        .collect();
+
+    // Recurrence
+    let parent = match task.attribute("parent") {
+        Some(parent_uuid) => linked_tasks(&[parent_uuid.as_str()], options)?
+            .into_iter()
+            .next(),
+        None => None,
+    };
+    let children = if task.status == "recurring" {
+        linked_tasks(&[&format!("parent:{}", uuid)], options)?
+    } else {
+        vec![]
+    };
+
+    let history = crate::undo::task_history(uuid, options)?;
+
+    Ok(TaskDetail {
+        uuid: task.uuid.clone(),
+        description: task.description.clone(),
+        modified: task.modified.as_ref().map(crate::dates::timestamp),
+        attributes,
+        annotations: task.annotations.clone(),
+        depends,
+        blocks,
+        parent,
+        children,
+        history_unsupported: history.is_none(),
+        history: history.unwrap_or_default(),
+    })
+}
@@ -0,0 +1,404 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+/// Urgency at or above which nodes get the hottest color
+const URGENCY_MAX: f64 = 15.0;
+
+const NODE_WIDTH: usize = 180;
+const NODE_HEIGHT: usize = 30;
+const LAYER_SPACING: usize = 60;
+const NODE_SPACING: usize = 15;
+const LABEL_MAX_CHARS: usize = 24;
+
+#[derive(serde::Serialize)]
+struct Node {
+    uuid: String,
+    description: String,
+    urgency: f64,
+    active: bool,
+    waiting: bool,
+    in_cycle: bool,
+    /// Length of the longest dependency chain below this node
+    layer: usize,
+}
+
+#[derive(serde::Serialize)]
+struct NodeRef {
+    uuid: String,
+    description: String,
+}
+
+/// Directed graph of pending tasks, with edges from tasks to their dependencies
+#[derive(serde::Serialize)]
+pub struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<(usize, usize)>,
+    cycles: Vec<Vec<NodeRef>>,
+    blocked_chains: Vec<Vec<NodeRef>>,
+}
+
+/// Find strongly connected components with more than one node or a self loop (Tarjan's algorithm)
+fn find_cycles(node_count: usize, successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct State<'a> {
+        successors: &'a [Vec<usize>],
+        index: usize,
+        indices: Vec<Option<usize>>,
+        low_links: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        cycles: Vec<Vec<usize>>,
+    }
+
+    fn visit(state: &mut State, v: usize) {
+        state.indices[v] = Some(state.index);
+        state.low_links[v] = state.index;
+        state.index += 1;
+        state.stack.push(v);
+        state.on_stack[v] = true;
+
+        let successors = state.successors;
+        for &w in &successors[v] {
+            match state.indices[w] {
+                None => {
+                    visit(state, w);
+                    state.low_links[v] = std::cmp::min(state.low_links[v], state.low_links[w]);
+                }
+                Some(w_index) if state.on_stack[w] => {
+                    state.low_links[v] = std::cmp::min(state.low_links[v], w_index);
+                }
+                _ => {}
+            }
+        }
+
+        if Some(state.low_links[v]) == state.indices[v] {
+            let mut component = Vec::new();
+            loop {
+                let w = state.stack.pop().unwrap();
+                state.on_stack[w] = false;
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            if component.len() > 1 || state.successors[v].contains(&v) {
+                component.sort_unstable();
+                state.cycles.push(component);
+            }
+        }
+    }
+
+    let mut state = State {
+        successors,
+        index: 0,
+        indices: vec![None; node_count],
+        low_links: vec![0; node_count],
+        stack: Vec::new(),
+        on_stack: vec![false; node_count],
+        cycles: Vec::new(),
+    };
+    for v in 0..node_count {
+        if state.indices[v].is_none() {
+            visit(&mut state, v);
+        }
+    }
+    state.cycles
+}
+
+/// Compute longest dependency chain below each node, ignoring edges inside cycles
+fn compute_layers(successors: &[Vec<usize>], component: &[Option<usize>]) -> Vec<usize> {
+    fn layer(
+        v: usize,
+        successors: &[Vec<usize>],
+        component: &[Option<usize>],
+        layers: &mut [Option<usize>],
+    ) -> usize {
+        if let Some(l) = layers[v] {
+            return l;
+        }
+        // Mark as visited before recursing to always terminate
+        layers[v] = Some(0);
+        let mut l = 0;
+        for &w in &successors[v] {
+            if component[v].is_some() && component[v] == component[w] {
+                continue;
+            }
+            l = std::cmp::max(l, layer(w, successors, component, layers) + 1);
+        }
+        layers[v] = Some(l);
+        l
+    }
+
+    let mut layers = vec![None; successors.len()];
+    (0..successors.len())
+        .map(|v| layer(v, successors, component, &mut layers))
+        .collect()
+}
+
+pub fn graph(options: &RunOpts) -> anyhow::Result<Graph> {
+    let tasks = tw::export(
+        &["(", "status:pending", "or", "status:waiting", ")"],
+        options,
+    )?;
+
+    // Keep only tasks involved in a dependency
+    let uuids: HashSet<&str> = tasks.iter().map(|t| t.uuid.as_str()).collect();
+    let dependencies: HashSet<&str> = tasks
+        .iter()
+        .flat_map(|t| t.depends.iter().map(String::as_str))
+        .collect();
+    let mut involved: Vec<&tw::ExportedTask> = tasks
+        .iter()
+        .filter(|t| {
+            t.depends.iter().any(|d| uuids.contains(d.as_str()))
+                || dependencies.contains(t.uuid.as_str())
+        })
+        .collect();
+    involved.sort_by(|a, b| b.urgency.partial_cmp(&a.urgency).unwrap());
+    let uuid_to_index: HashMap<&str, usize> = involved
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (t.uuid.as_str(), i))
+        .collect();
+
+    let successors: Vec<Vec<usize>> = involved
+        .iter()
+        .map(|t| {
+            t.depends
+                .iter()
+                .filter_map(|d| uuid_to_index.get(d.as_str()).copied())
+                .collect()
+        })
+        .collect();
+    let cycles = find_cycles(involved.len(), &successors);
+    let mut component = vec![None; involved.len()];
+    for (i, cycle) in cycles.iter().enumerate() {
+        for &v in cycle {
+            component[v] = Some(i);
+        }
+    }
+    let layers = compute_layers(&successors, &component);
+
+    // Longest blocked chain from each task nothing depends on
+    let mut has_predecessor = vec![false; involved.len()];
+    for s in successors.iter().flatten() {
+        has_predecessor[*s] = true;
+    }
+    let blocked_chains: Vec<Vec<usize>> = (0..involved.len())
+        .filter(|v| !has_predecessor[*v] && layers[*v] > 0)
+        .map(|v| {
+            let mut chain = vec![v];
+            let mut cur = v;
+            loop {
+                let next = successors[cur].iter().copied().find(|&w| {
+                    (component[cur].is_none() || component[cur] != component[w])
+                        && layers[w] + 1 == layers[cur]
+                });
+                match next {
+                    Some(next) => {
+                        chain.push(next);
+                        cur = next;
+                    }
+                    None => break,
+                }
+            }
+            chain
+        })
+        .collect();
+
+    let node_refs = |indices: &Vec<usize>| -> Vec<NodeRef> {
+        indices
+            .iter()
+            .map(|i| NodeRef {
+                uuid: involved[*i].uuid.clone(),
+                description: involved[*i].description.clone(),
+            })
+            .collect()
+    };
+    let cycle_refs = cycles.iter().map(node_refs).collect();
+    let blocked_chain_refs = blocked_chains.iter().map(node_refs).collect();
+
+    let nodes = involved
+        .iter()
+        .zip(layers)
+        .enumerate()
+        .map(|(i, (t, layer))| Node {
+            uuid: t.uuid.clone(),
+            description: t.description.clone(),
+            urgency: t.urgency,
+            active: t.start.is_some(),
+            waiting: t.status == "waiting",
+            in_cycle: component[i].is_some(),
+            layer,
+        })
+        .collect();
+    let edges = successors
+        .iter()
+        .enumerate()
+        .flat_map(|(v, ws)| ws.iter().map(move |w| (v, *w)))
+        .collect();
+
+    Ok(Graph {
+        nodes,
+        edges,
+        cycles: cycle_refs,
+        blocked_chains: blocked_chain_refs,
+    })
+}
+
+/// Get node hue, from green (0 urgency) to red
+fn urgency_hue(urgency: f64) -> f64 {
+    120.0 * (1.0 - urgency.clamp(0.0, URGENCY_MAX) / URGENCY_MAX)
+}
+
+fn truncate_label(description: &str) -> String {
+    if description.chars().count() > LABEL_MAX_CHARS {
+        let mut r: String = description.chars().take(LABEL_MAX_CHARS - 1).collect();
+        r.push('…');
+        r
+    } else {
+        description.to_string()
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Graph {
+    /// Render as Graphviz DOT
+    pub fn to_dot(&self) -> String {
+        let mut r = String::new();
+        writeln!(r, "digraph dependencies {{").unwrap();
+        writeln!(r, "  rankdir=RL;").unwrap();
+        writeln!(r, "  node [shape=box, style=filled];").unwrap();
+        for node in &self.nodes {
+            let mut style = vec!["filled"];
+            if node.active {
+                style.push("bold");
+            }
+            if node.waiting {
+                style.push("dashed");
+            }
+            writeln!(
+                r,
+                "  \"{}\" [label=\"{}\", URL=\"/task/{}\", fillcolor=\"{:.3} 0.4 1.0\", style=\"{}\"{}];",
+                node.uuid,
+                escape_dot(&truncate_label(&node.description)),
+                node.uuid,
+                urgency_hue(node.urgency) / 360.0,
+                style.join(","),
+                if node.in_cycle { ", color=red" } else { "" }
+            )
+            .unwrap();
+        }
+        for (from, to) in &self.edges {
+            writeln!(
+                r,
+                "  \"{}\" -> \"{}\";",
+                self.nodes[*from].uuid, self.nodes[*to].uuid
+            )
+            .unwrap();
+        }
+        writeln!(r, "}}").unwrap();
+        r
+    }
+
+    /// Render as SVG, with dependencies on the left of the tasks they block
+    pub fn to_svg(&self) -> String {
+        // Position nodes by layer
+        let mut layer_counts: Vec<usize> = Vec::new();
+        let positions: Vec<(usize, usize)> = self
+            .nodes
+            .iter()
+            .map(|n| {
+                if layer_counts.len() <= n.layer {
+                    layer_counts.resize(n.layer + 1, 0);
+                }
+                let row = layer_counts[n.layer];
+                layer_counts[n.layer] += 1;
+                (
+                    NODE_SPACING + n.layer * (NODE_WIDTH + LAYER_SPACING),
+                    NODE_SPACING + row * (NODE_HEIGHT + NODE_SPACING),
+                )
+            })
+            .collect();
+        let width = NODE_SPACING + layer_counts.len() * (NODE_WIDTH + LAYER_SPACING);
+        let height = NODE_SPACING
+            + layer_counts.iter().max().copied().unwrap_or(0) * (NODE_HEIGHT + NODE_SPACING);
+
+        let mut r = String::new();
+        writeln!(
+            r,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+            width, height, width, height
+        )
+        .unwrap();
+        writeln!(
+            r,
+            "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\
+             <path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"currentColor\"/></marker></defs>"
+        )
+        .unwrap();
+
+        for (from, to) in &self.edges {
+            let (from_x, from_y) = positions[*from];
+            let (to_x, to_y) = positions[*to];
+            writeln!(
+                r,
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" marker-end=\"url(#arrow)\"/>",
+                from_x,
+                from_y + NODE_HEIGHT / 2,
+                to_x + NODE_WIDTH,
+                to_y + NODE_HEIGHT / 2,
+                if self.nodes[*from].in_cycle && self.nodes[*to].in_cycle {
+                    "red"
+                } else {
+                    "currentColor"
+                }
+            )
+            .unwrap();
+        }
+
+        for (node, (x, y)) in self.nodes.iter().zip(positions) {
+            writeln!(
+                r,
+                "<a href=\"/task/{uuid}\"><rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" rx=\"4\" \
+                 fill=\"hsl({hue:.0}, 70%, 80%)\" stroke=\"{stroke}\" stroke-width=\"{sw}\"{dash}>\
+                 <title>{title} (urgency {urgency:.1})</title></rect>\
+                 <text x=\"{tx}\" y=\"{ty}\" font-size=\"12\" fill=\"black\">{label}</text></a>",
+                uuid = node.uuid,
+                x = x,
+                y = y,
+                w = NODE_WIDTH,
+                h = NODE_HEIGHT,
+                hue = urgency_hue(node.urgency),
+                stroke = if node.in_cycle { "red" } else { "black" },
+                sw = if node.active { 3 } else { 1 },
+                dash = if node.waiting {
+                    " stroke-dasharray=\"4\""
+                } else {
+                    ""
+                },
+                title = escape_xml(&node.description),
+                urgency = node.urgency,
+                tx = x + 6,
+                ty = y + NODE_HEIGHT / 2 + 4,
+                label = escape_xml(&truncate_label(&node.description)),
+            )
+            .unwrap();
+        }
+
+        r.push_str("</svg>");
+        r
+    }
+}
@@ -0,0 +1,41 @@
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+/// Keyboard actions with their default key, overridden by `taskboard.keymap.<action>` entries in taskrc
+static ACTIONS: [(&str, &str, &str); 9] = [
+    ("down", "j", "Select next task"),
+    ("up", "k", "Select previous task"),
+    ("edit", "e", "Edit selected task"),
+    ("done", "d", "Mark selected task as done"),
+    ("add", "a", "Add a task"),
+    ("filter", "/", "Filter report"),
+    ("shell", ":", "Run a task command"),
+    ("undo", "u", "Undo last change"),
+    ("palette", "ctrl+k", "Open command palette"),
+];
+
+#[derive(Debug, serde::Serialize)]
+pub struct Binding {
+    action: String,
+    /// Key as in `KeyboardEvent.key`, optionally prefixed by `ctrl+`, `alt+`, then `shift+` for keys
+    /// not producing a character, like `shift+ArrowDown`
+    key: String,
+    description: String,
+}
+
+pub fn keymap(options: &RunOpts) -> anyhow::Result<Vec<Binding>> {
+    let config = tw::config(options)?;
+    Ok(ACTIONS
+        .iter()
+        .map(|(action, key, description)| Binding {
+            action: action.to_string(),
+            key: config
+                .get(&format!("taskboard.keymap.{}", action))
+                .map(|k| k.trim())
+                .filter(|k| !k.is_empty())
+                .unwrap_or(key)
+                .to_string(),
+            description: description.to_string(),
+        })
+        .collect())
+}
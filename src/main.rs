@@ -7,10 +7,27 @@ extern crate lazy_static;
 extern crate rocket;
 
 mod assets;
+mod board;
+mod bulk;
+mod calendar;
+mod charts;
+mod colors;
+mod conflict;
+mod dates;
+mod detail;
+mod graph;
+mod keymap;
+mod metrics;
+mod projects;
+mod recurring;
 mod run_opts;
+mod templates;
 #[cfg(test)]
 mod test;
 mod tw;
+mod udas;
+mod undo;
+mod views;
 
 //
 // Reports
@@ -19,33 +36,351 @@ mod tw;
 #[derive(serde::Serialize)]
 struct TemplateContext {
     title: String,
+    report_name: String,
+    reports: Vec<views::ReportInfo>,
+    overrides: tw::ReportOverrides,
     report: tw::Report,
 }
 
+fn render_report(
+    report_name: &str,
+    overrides: tw::ReportOverrides,
+    options: &run_opts::RunOpts,
+) -> anyhow::Result<templates::Template> {
+    let config = tw::config(options)?;
+    let report = tw::report(report_name, &config, &overrides, options)?;
+    let context = TemplateContext {
+        title: format!("{} report", report_name),
+        report_name: report_name.to_string(),
+        reports: views::reports_from_config(&config),
+        overrides,
+        report,
+    };
+    Ok(templates::Template::render("layout", &context))
+}
+
 #[get("/")]
 fn report_default(
     options: rocket::State<run_opts::RunOpts>,
-) -> anyhow::Result<rocket_contrib::templates::Template> {
-    report(rocket::http::RawStr::from_str("next"), options) // TODO get default report dynamically?
+) -> anyhow::Result<templates::Template> {
+    render_report("next", Default::default(), &options) // TODO get default report dynamically?
 }
 
-#[get("/<report_name>")]
-#[allow(clippy::unnecessary_wraps)]
+#[get("/<report_name>?<overrides..>")]
 fn report(
-    report_name: &rocket::http::RawStr,
+    report_name: String,
+    overrides: rocket::request::LenientForm<tw::ReportOverrides>,
     options: rocket::State<run_opts::RunOpts>,
-) -> anyhow::Result<rocket_contrib::templates::Template> {
-    let report = tw::report(report_name, &*options).unwrap(); //or_else(|_| Err(rocket::http::Status::NotFound))?;
-    let context = TemplateContext {
-        title: format!("{} report", report_name),
-        report,
+) -> anyhow::Result<templates::Template> {
+    render_report(&report_name, overrides.into_inner(), &options)
+}
+
+#[post("/views", format = "json", data = "<view>")]
+fn save_view(
+    view: rocket_contrib::json::Json<views::SavedView>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket_contrib::json::Json<views::ReportDefinition>> {
+    Ok(rocket_contrib::json::Json(views::save_view(
+        &view, &options,
+    )?))
+}
+
+#[derive(serde::Serialize)]
+struct ReportEditorTemplateContext {
+    title: String,
+    name: String,
+    definition: views::ReportDefinition,
+    column_names: Vec<String>,
+}
+
+#[get("/reports?<name>")]
+fn report_editor(
+    name: Option<String>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<templates::Template> {
+    let name = name.unwrap_or_default();
+    let definition = if name.is_empty() {
+        Default::default()
+    } else {
+        views::report_definition(&name, &options)?
+    };
+    let context = ReportEditorTemplateContext {
+        title: if name.is_empty() {
+            "New report".to_string()
+        } else {
+            format!("Edit {} report", name)
+        },
+        name,
+        definition,
+        column_names: tw::column_names(),
+    };
+    Ok(templates::Template::render("report_editor", &context))
+}
+
+#[post("/reports", format = "json", data = "<edit>")]
+fn report_edit(
+    edit: rocket_contrib::json::Json<views::ReportEdit>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket_contrib::json::Json<views::ReportEditResult>> {
+    Ok(rocket_contrib::json::Json(views::edit(&edit, &options)?))
+}
+
+/// Reports for the command palette
+#[get("/reports/list")]
+fn report_list(
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket_contrib::json::Json<Vec<views::ReportInfo>>> {
+    Ok(rocket_contrib::json::Json(views::reports(&options)?))
+}
+
+/// Keyboard bindings, read from taskrc on each request so changes apply without restart
+#[get("/keymap")]
+fn key_bindings(
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket_contrib::json::Json<Vec<keymap::Binding>>> {
+    Ok(rocket_contrib::json::Json(keymap::keymap(&options)?))
+}
+
+#[derive(serde::Serialize)]
+struct Updates {
+    version: String,
+}
+
+/// Task data version, polled by pages to reload when tasks change
+#[get("/updates")]
+fn updates(
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket_contrib::json::Json<Updates>> {
+    Ok(rocket_contrib::json::Json(Updates {
+        version: tw::data_version(&options)?,
+    }))
+}
+
+//
+// UDAs
+//
+
+#[derive(serde::Serialize)]
+struct UdasTemplateContext {
+    title: String,
+    udas: Vec<udas::Uda>,
+}
+
+#[get("/udas")]
+fn udas_page(options: rocket::State<run_opts::RunOpts>) -> anyhow::Result<templates::Template> {
+    let context = UdasTemplateContext {
+        title: "User defined attributes".to_string(),
+        udas: udas::udas(&options)?,
     };
-    // TODO bundle templates, see https://github.com/SergioBenitez/Rocket/issues/943
-    Ok(rocket_contrib::templates::Template::render(
-        "layout", &context,
+    Ok(templates::Template::render("udas", &context))
+}
+
+#[post("/udas", format = "json", data = "<uda>")]
+fn uda_save(
+    uda: rocket_contrib::json::Json<udas::Uda>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket_contrib::json::Json<Vec<udas::Uda>>> {
+    udas::save(&uda, &options)?;
+    Ok(rocket_contrib::json::Json(udas::udas(&options)?))
+}
+
+//
+// Board
+//
+
+#[derive(serde::Serialize)]
+struct BoardTemplateContext {
+    title: String,
+    board: board::Board,
+}
+
+#[get("/board?<group>")]
+fn board(
+    group: Option<String>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<templates::Template> {
+    let attribute = group.unwrap_or_else(|| "status".to_string());
+    let board = board::board(&attribute, &options)?;
+    let context = BoardTemplateContext {
+        title: format!("{} board", attribute),
+        board,
+    };
+    Ok(templates::Template::render("board", &context))
+}
+
+//
+// Calendar
+//
+
+#[derive(serde::Serialize)]
+struct CalendarTemplateContext {
+    title: String,
+    calendar: calendar::Calendar,
+}
+
+#[get("/calendar?<month>")]
+fn calendar(
+    month: Option<String>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<templates::Template> {
+    let calendar = calendar::calendar(month.as_deref(), &options)?;
+    let context = CalendarTemplateContext {
+        title: "Calendar".to_string(),
+        calendar,
+    };
+    Ok(templates::Template::render("calendar", &context))
+}
+
+//
+// Charts
+//
+
+#[derive(serde::Serialize)]
+struct ChartTemplateContext {
+    title: String,
+    chart: String,
+    svg: String,
+}
+
+fn period_history(
+    period: Option<String>,
+    options: &run_opts::RunOpts,
+) -> anyhow::Result<(String, Vec<charts::PeriodCounts>)> {
+    let period_str = period.unwrap_or_else(|| "daily".to_string());
+    let period: charts::Period = period_str.parse()?;
+    let tasks = tw::export(&[], options)?;
+    Ok((period_str, charts::history(&tasks, period)))
+}
+
+#[get("/charts/burndown?<period>")]
+fn chart_burndown(
+    period: Option<String>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<templates::Template> {
+    let (period, history) = period_history(period, &options)?;
+    let context = ChartTemplateContext {
+        title: format!("{} burndown", period),
+        chart: "burndown".to_string(),
+        svg: charts::burndown_svg(&history),
+    };
+    Ok(templates::Template::render("chart", &context))
+}
+
+#[get("/charts/history?<period>")]
+fn chart_history(
+    period: Option<String>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<templates::Template> {
+    let (period, history) = period_history(period, &options)?;
+    let context = ChartTemplateContext {
+        title: format!("{} history", period),
+        chart: "history".to_string(),
+        svg: charts::history_svg(&history),
+    };
+    Ok(templates::Template::render("chart", &context))
+}
+
+//
+// Task detail
+//
+
+#[derive(serde::Serialize)]
+struct TaskTemplateContext {
+    title: String,
+    task: detail::TaskDetail,
+}
+
+#[get("/task/<uuid>")]
+fn task(
+    uuid: String,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<templates::Template> {
+    let task = detail::task_detail(&uuid, &options)?;
+    let context = TaskTemplateContext {
+        title: task.description.clone(),
+        task,
+    };
+    Ok(templates::Template::render("task", &context))
+}
+
+//
+// Dependency graph
+//
+
+#[derive(serde::Serialize)]
+struct GraphTemplateContext {
+    title: String,
+    svg: String,
+    graph: graph::Graph,
+}
+
+#[get("/graph")]
+fn graph(options: rocket::State<run_opts::RunOpts>) -> anyhow::Result<templates::Template> {
+    let graph = graph::graph(&options)?;
+    let context = GraphTemplateContext {
+        title: "Dependencies".to_string(),
+        svg: graph.to_svg(),
+        graph,
+    };
+    Ok(templates::Template::render("graph", &context))
+}
+
+#[get("/graph?format=dot")]
+fn graph_dot(
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket::response::content::Content<String>> {
+    let graph = graph::graph(&options)?;
+    Ok(rocket::response::content::Content(
+        rocket::http::ContentType::new("text", "vnd.graphviz"),
+        graph.to_dot(),
     ))
 }
 
+//
+// Projects
+//
+
+#[derive(serde::Serialize)]
+struct ProjectsTemplateContext {
+    title: String,
+    report_name: String,
+    projects: Vec<projects::ProjectNode>,
+}
+
+#[get("/projects?<report>")]
+fn projects(
+    report: Option<String>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<templates::Template> {
+    let context = ProjectsTemplateContext {
+        title: "Projects".to_string(),
+        report_name: report.unwrap_or_else(|| "next".to_string()),
+        projects: projects::project_tree(&options)?,
+    };
+    Ok(templates::Template::render("projects", &context))
+}
+
+//
+// Recurring tasks
+//
+
+#[derive(serde::Serialize)]
+struct RecurringTemplateContext {
+    title: String,
+    templates: Vec<recurring::RecurringTemplate>,
+}
+
+#[get("/templates/recurring")]
+fn recurring_page(
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<templates::Template> {
+    let context = RecurringTemplateContext {
+        title: "Recurring tasks".to_string(),
+        templates: recurring::templates(&options)?,
+    };
+    Ok(templates::Template::render("recurring", &context))
+}
+
 //
 // XHR
 //
@@ -56,6 +391,10 @@ struct CmdResult {
     code: i32,
 }
 
+/// Mutation result, or 409 Conflict when tasks changed since they were displayed
+type MutationResult<T> =
+    anyhow::Result<Result<rocket_contrib::json::Json<T>, conflict::ConflictResponse>>;
+
 #[post("/shell", format = "json", data = "<cmd>")]
 fn cmd(
     cmd: rocket_contrib::json::Json<String>,
@@ -72,35 +411,332 @@ fn cmd(
     }))
 }
 
+#[derive(serde::Deserialize)]
+struct BoardMove {
+    uuid: String,
+    value: String,
+}
+
+/// Move a task to a board column, attributes tasks can not be grouped by are rejected with 400
+#[post("/board/<attribute>", format = "json", data = "<board_move>")]
+fn board_move(
+    attribute: String,
+    board_move: rocket_contrib::json::Json<BoardMove>,
+    since: conflict::IfUnmodifiedSince,
+    options: rocket::State<run_opts::RunOpts>,
+) -> Result<MutationResult<CmdResult>, rocket::http::Status> {
+    match board::is_groupable(&attribute, &options) {
+        Ok(true) => Ok(move_task(&attribute, &board_move, &since, &options)),
+        Ok(false) => Err(rocket::http::Status::BadRequest),
+        Err(e) => Ok(Err(e)),
+    }
+}
+
+fn move_task(
+    attribute: &str,
+    board_move: &BoardMove,
+    since: &conflict::IfUnmodifiedSince,
+    options: &run_opts::RunOpts,
+) -> MutationResult<CmdResult> {
+    if let Err(conflict) = since.check(&board_move.uuid, options)? {
+        return Ok(Err(conflict));
+    }
+    let args = board::move_args(&board_move.uuid, attribute, &board_move.value)?;
+    let args: Vec<&str> = args.iter().map(AsRef::as_ref).collect();
+    let res = tw::invoke_external(&args[..], options)?;
+
+    Ok(Ok(rocket_contrib::json::Json(CmdResult {
+        output: res.1,
+        code: res.0,
+    })))
+}
+
+#[derive(serde::Deserialize)]
+struct TaskModification {
+    attribute: String,
+    value: String,
+}
+
+#[post("/task/<uuid>", format = "json", data = "<modification>")]
+fn task_modify(
+    uuid: String,
+    modification: rocket_contrib::json::Json<TaskModification>,
+    since: conflict::IfUnmodifiedSince,
+    options: rocket::State<run_opts::RunOpts>,
+) -> MutationResult<CmdResult> {
+    if let Err(conflict) = since.check(&uuid, &options)? {
+        return Ok(Err(conflict));
+    }
+    tw::validate_value(&modification.attribute, &modification.value, &options)?;
+    let modification_arg = if modification.attribute == "tags" {
+        modification.value.clone()
+    } else {
+        format!("{}:{}", modification.attribute, modification.value)
+    };
+    let res = tw::invoke_external(&[&uuid, "modify", &modification_arg], &options)?;
+
+    Ok(Ok(rocket_contrib::json::Json(CmdResult {
+        output: res.1,
+        code: res.0,
+    })))
+}
+
+#[derive(serde::Serialize)]
+struct CalcResult {
+    expression: String,
+    result: String,
+}
+
+#[get("/calc?<expression>")]
+fn calc(
+    expression: String,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket_contrib::json::Json<CalcResult>> {
+    let result = tw::calc(&expression, &options)?;
+    Ok(rocket_contrib::json::Json(CalcResult {
+        expression,
+        result,
+    }))
+}
+
+#[post(
+    "/templates/recurring/<uuid>",
+    format = "json",
+    data = "<modification>"
+)]
+fn recurring_modify(
+    uuid: String,
+    modification: rocket_contrib::json::Json<recurring::TemplateModification>,
+    since: conflict::IfUnmodifiedSince,
+    options: rocket::State<run_opts::RunOpts>,
+) -> MutationResult<CmdResult> {
+    if let Err(conflict) = since.check(&uuid, &options)? {
+        return Ok(Err(conflict));
+    }
+    let res = recurring::modify(&uuid, &modification, &options)?;
+
+    Ok(Ok(rocket_contrib::json::Json(CmdResult {
+        output: res.1,
+        code: res.0,
+    })))
+}
+
+#[delete("/templates/recurring/<uuid>?<delete_children>")]
+fn recurring_stop(
+    uuid: String,
+    delete_children: Option<bool>,
+    since: conflict::IfUnmodifiedSince,
+    options: rocket::State<run_opts::RunOpts>,
+) -> MutationResult<CmdResult> {
+    if let Err(conflict) = since.check(&uuid, &options)? {
+        return Ok(Err(conflict));
+    }
+    let res = recurring::stop(&uuid, delete_children.unwrap_or(false), &options)?;
+
+    Ok(Ok(rocket_contrib::json::Json(CmdResult {
+        output: res.1,
+        code: res.0,
+    })))
+}
+
+#[post("/bulk", format = "json", data = "<request>")]
+fn bulk(
+    request: rocket_contrib::json::Json<bulk::BulkRequest>,
+    options: rocket::State<run_opts::RunOpts>,
+) -> MutationResult<bulk::BulkResult> {
+    if let Err(conflict) = conflict::check(&request.unmodified_since()?, &options)? {
+        return Ok(Err(conflict));
+    }
+    Ok(Ok(rocket_contrib::json::Json(bulk::run(
+        &request, &options,
+    )?)))
+}
+
+#[get("/task/<uuid>/annotations")]
+fn annotations(
+    uuid: String,
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket_contrib::json::Json<Vec<tw::Annotation>>> {
+    Ok(rocket_contrib::json::Json(tw::annotations(
+        &uuid, &options,
+    )?))
+}
+
+#[derive(serde::Deserialize)]
+struct AnnotationChange {
+    /// Previous text, when editing
+    #[serde(default)]
+    old: Option<String>,
+    description: String,
+}
+
+#[post("/task/<uuid>/annotations", format = "json", data = "<change>")]
+fn annotation_add(
+    uuid: String,
+    change: rocket_contrib::json::Json<AnnotationChange>,
+    since: conflict::IfUnmodifiedSince,
+    options: rocket::State<run_opts::RunOpts>,
+) -> MutationResult<CmdResult> {
+    if let Err(conflict) = since.check(&uuid, &options)? {
+        return Ok(Err(conflict));
+    }
+    let res = tw::annotate(&uuid, &change.description, &options)?;
+
+    Ok(Ok(rocket_contrib::json::Json(CmdResult {
+        output: res.1,
+        code: res.0,
+    })))
+}
+
+#[put("/task/<uuid>/annotations", format = "json", data = "<change>")]
+fn annotation_edit(
+    uuid: String,
+    change: rocket_contrib::json::Json<AnnotationChange>,
+    since: conflict::IfUnmodifiedSince,
+    options: rocket::State<run_opts::RunOpts>,
+) -> MutationResult<CmdResult> {
+    if let Err(conflict) = since.check(&uuid, &options)? {
+        return Ok(Err(conflict));
+    }
+    let old = change
+        .old
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Missing previous annotation text"))?;
+    let res = tw::edit_annotation(&uuid, old, &change.description, &options)?;
+
+    Ok(Ok(rocket_contrib::json::Json(CmdResult {
+        output: res.1,
+        code: res.0,
+    })))
+}
+
+#[delete("/task/<uuid>/annotations", format = "json", data = "<change>")]
+fn annotation_remove(
+    uuid: String,
+    change: rocket_contrib::json::Json<AnnotationChange>,
+    since: conflict::IfUnmodifiedSince,
+    options: rocket::State<run_opts::RunOpts>,
+) -> MutationResult<CmdResult> {
+    if let Err(conflict) = since.check(&uuid, &options)? {
+        return Ok(Err(conflict));
+    }
+    let res = tw::denotate(&uuid, &change.description, &options)?;
+
+    Ok(Ok(rocket_contrib::json::Json(CmdResult {
+        output: res.1,
+        code: res.0,
+    })))
+}
+
+//
+// Metrics
+//
+
+#[get("/metrics")]
+fn metrics(
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<rocket::response::content::Content<String>> {
+    let mut task_counts = Vec::new();
+    for status in &["pending", "waiting", "completed"] {
+        let filter = format!("status:{}", status);
+        task_counts.push((*status, tw::count(&[&filter], &options)?));
+    }
+
+    Ok(rocket::response::content::Content(
+        rocket::http::ContentType::with_params("text", "plain", ("version", "0.0.4")),
+        metrics::render(&task_counts),
+    ))
+}
+
 //
 // Assets
 //
 
 #[get("/static/<path..>")]
-fn asset(path: std::path::PathBuf, last_mod: assets::IfModified) -> rocket::response::Result {
-    let filepath = path
-        .clone()
+fn asset(
+    path: std::path::PathBuf,
+    last_mod: assets::IfModified,
+    if_none_match: assets::IfNoneMatch,
+    accept_encoding: assets::AcceptEncoding,
+) -> rocket::response::Result {
+    let mut filepath = path
         .into_os_string()
         .into_string()
         .map_err(|_| rocket::http::Status::NotFound)?;
-    assets::Assets::get(&filepath).map_or_else(
-        || Err(rocket::http::Status::NotFound),
-        |d| {
-            let ext = path
-                .extension()
-                .and_then(std::ffi::OsStr::to_str)
-                .unwrap_or_else(|| panic!("Could not get extension from path {}", filepath));
-            let content_type =
-                rocket::http::ContentType::from_extension(ext).unwrap_or_else(|| {
-                    panic!("Could not guess file content type from extension {}", ext)
-                });
-            rocket::response::Response::build()
-                .header(content_type)
-                .raw_header("Last-Modified", last_mod.time)
-                .sized_body(std::io::Cursor::new(d))
-                .ok()
-        },
-    )
+    // versioned URLs never change, previous versions are served with current content
+    let mut versioned = false;
+    if let Some((version, unversioned)) = filepath.split_once('/') {
+        if version.len() == assets::VERSION.len() && version.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            versioned = version == *assets::VERSION;
+            filepath = unversioned.to_string();
+        }
+    }
+
+    let (data, encoding) = assets::ENCODINGS
+        .iter()
+        .filter(|(encoding, _)| accept_encoding.accepts(encoding))
+        .find_map(|(encoding, ext)| {
+            Some((
+                assets::get(&format!("{}.{}", filepath, ext))?,
+                Some(*encoding),
+            ))
+        })
+        .or_else(|| Some((assets::get(&filepath)?, None)))
+        .ok_or(rocket::http::Status::NotFound)?;
+    let etag = assets::etag(&filepath, encoding);
+    if etag.as_ref().map_or(false, |e| if_none_match.matches(e)) {
+        return Err(rocket::http::Status::NotModified);
+    }
+
+    let content_type = std::path::Path::new(&filepath)
+        .extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .and_then(rocket::http::ContentType::from_extension)
+        .unwrap_or(rocket::http::ContentType::Binary);
+    let mut response = rocket::response::Response::build();
+    response
+        .header(content_type)
+        .raw_header("Last-Modified", last_mod.time)
+        .raw_header("Vary", "Accept-Encoding")
+        .raw_header(
+            "Cache-Control",
+            if versioned {
+                "public, max-age=31536000, immutable"
+            } else {
+                "no-cache"
+            },
+        );
+    if let Some(etag) = etag {
+        response.raw_header("ETag", etag);
+    }
+    if let Some(encoding) = encoding {
+        response.raw_header("Content-Encoding", encoding);
+    }
+    response.sized_body(std::io::Cursor::new(data)).ok()
+}
+
+/// Service worker, served from the root to control all pages, its cache named by assets version
+#[get("/sw.js")]
+fn service_worker() -> Option<rocket::response::content::JavaScript<String>> {
+    assets::get("sw.js").map(|d| {
+        rocket::response::content::JavaScript(
+            String::from_utf8_lossy(&d).replace("__VERSION__", &assets::VERSION),
+        )
+    })
+}
+
+/// User stylesheet, read on each request so it can be tweaked without restarting
+#[get("/user.css")]
+fn user_css(
+    options: rocket::State<run_opts::RunOpts>,
+) -> anyhow::Result<Option<rocket::response::content::Css<String>>> {
+    match &options.user_css {
+        Some(path) => Ok(Some(rocket::response::content::Css(
+            std::fs::read_to_string(path)?,
+        ))),
+        None => Ok(None),
+    }
 }
 
 #[catch(304)]
@@ -116,11 +752,21 @@ lazy_static! {
         m.insert(tw::AttributeType::DateTime, "dt".to_string());
         m.insert(tw::AttributeType::String, "str".to_string());
         m.insert(tw::AttributeType::Numeric, "num".to_string());
+        m.insert(tw::AttributeType::Duration, "dur".to_string());
         m.insert(tw::AttributeType::Uda, "str".to_string());
         m
     };
 }
 
+fn asset_url(
+    filepath: rocket_contrib::templates::tera::Value,
+    _args: std::collections::HashMap<String, rocket_contrib::templates::tera::Value>,
+) -> rocket_contrib::templates::tera::Result<rocket_contrib::templates::tera::Value> {
+    let filepath =
+        rocket_contrib::templates::tera::try_get_value!("asset_url", "value", String, filepath);
+    Ok(rocket_contrib::templates::tera::to_value(assets::url(&filepath)).unwrap())
+}
+
 fn column_html_classes(
     column: rocket_contrib::templates::tera::Value,
     _args: std::collections::HashMap<String, rocket_contrib::templates::tera::Value>,
@@ -141,20 +787,79 @@ fn column_html_classes(
     Ok(rocket_contrib::templates::tera::to_value(r).unwrap())
 }
 
+fn color_classes(
+    rules: rocket_contrib::templates::tera::Value,
+    _args: std::collections::HashMap<String, rocket_contrib::templates::tera::Value>,
+) -> rocket_contrib::templates::tera::Result<rocket_contrib::templates::tera::Value> {
+    let rules = rocket_contrib::templates::tera::try_get_value!(
+        "color_classes",
+        "value",
+        Vec<String>,
+        rules
+    );
+    let classes: Vec<String> = rules
+        .iter()
+        .map(|r| format!("color-{}", r.replace('.', "-")))
+        .collect();
+    Ok(rocket_contrib::templates::tera::to_value(classes.join(" ")).unwrap())
+}
+
 //
 // Main
 //
 
 fn rocket(options: run_opts::RunOpts) -> rocket::Rocket {
+    tw::refresh_column_types(&options).expect("Failed to get column types");
+    let templates = templates::Templates::new(options.templates_dir.clone(), |tera| {
+        tera.register_filter("asset_url", asset_url);
+        tera.register_filter("color_classes", color_classes);
+        tera.register_filter("column_classes", column_html_classes);
+    })
+    .and_then(|t| t.global("stylesheet", &options.theme.stylesheet()))
+    .and_then(|t| t.global("user_css", &options.user_css.is_some()))
+    .expect("Failed to load templates");
     rocket::ignite()
-        .attach(rocket_contrib::templates::Template::custom(
-            |engines: &mut rocket_contrib::templates::Engines| {
-                engines
-                    .tera
-                    .register_filter("column_classes", column_html_classes);
-            },
-        ))
-        .mount("/", routes![report_default, report, cmd, asset])
+        .manage(templates)
+        .attach(metrics::RequestCounter)
+        .mount(
+            "/",
+            routes![
+                report_default,
+                report,
+                board,
+                calendar,
+                chart_burndown,
+                chart_history,
+                graph,
+                graph_dot,
+                projects,
+                task,
+                cmd,
+                board_move,
+                task_modify,
+                calc,
+                recurring_page,
+                recurring_modify,
+                recurring_stop,
+                bulk,
+                annotations,
+                annotation_add,
+                annotation_edit,
+                annotation_remove,
+                save_view,
+                report_editor,
+                report_edit,
+                report_list,
+                key_bindings,
+                updates,
+                udas_page,
+                uda_save,
+                metrics,
+                asset,
+                service_worker,
+                user_css
+            ],
+        )
         .register(catchers![not_modified])
         .manage(options)
 }
@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Upper bounds of the task invocation duration histogram buckets, in seconds
+const TASK_DURATION_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+struct Histogram {
+    bucket_counts: [u64; TASK_DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Histogram {
+            bucket_counts: [0; TASK_DURATION_BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket_count, bound) in self
+            .bucket_counts
+            .iter_mut()
+            .zip(TASK_DURATION_BUCKETS.iter())
+        {
+            if value <= *bound {
+                *bucket_count += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Default)]
+struct Metrics {
+    /// subcommand -> duration histogram
+    task_durations: BTreeMap<String, Histogram>,
+    /// (subcommand, exit code) -> count
+    task_failures: BTreeMap<(String, String), u64>,
+    /// (method, route, status code) -> count
+    http_requests: BTreeMap<(String, String, u16), u64>,
+}
+
+lazy_static! {
+    static ref METRICS: std::sync::Mutex<Metrics> = std::sync::Mutex::new(Metrics::default());
+}
+
+/// Record a finished task invocation
+pub fn observe_task_invocation(
+    subcommand: &str,
+    duration: std::time::Duration,
+    status: std::process::ExitStatus,
+) {
+    let mut metrics = METRICS.lock().unwrap();
+    metrics
+        .task_durations
+        .entry(subcommand.to_string())
+        .or_default()
+        .observe(duration.as_secs_f64());
+    if !status.success() {
+        let code = status
+            .code()
+            .map_or_else(|| "signal".to_string(), |c| c.to_string());
+        *metrics
+            .task_failures
+            .entry((subcommand.to_string(), code))
+            .or_default() += 1;
+    }
+}
+
+/// Fairing counting responses per route
+pub struct RequestCounter;
+
+impl rocket::fairing::Fairing for RequestCounter {
+    fn info(&self) -> rocket::fairing::Info {
+        rocket::fairing::Info {
+            name: "Request counter",
+            kind: rocket::fairing::Kind::Response,
+        }
+    }
+
+    fn on_response(&self, request: &rocket::Request, response: &mut rocket::Response) {
+        let route = request
+            .route()
+            .map_or_else(|| "none".to_string(), |r| r.uri.path().to_string());
+        let mut metrics = METRICS.lock().unwrap();
+        *metrics
+            .http_requests
+            .entry((
+                request.method().as_str().to_string(),
+                route,
+                response.status().code,
+            ))
+            .or_default() += 1;
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Render all metrics in the Prometheus text exposition format
+pub fn render(task_counts: &[(&str, u64)]) -> String {
+    let metrics = METRICS.lock().unwrap();
+    let mut r = String::new();
+
+    writeln!(
+        r,
+        "# HELP task_board_task_duration_seconds Duration of task invocations."
+    )
+    .unwrap();
+    writeln!(r, "# TYPE task_board_task_duration_seconds histogram").unwrap();
+    for (subcommand, histogram) in &metrics.task_durations {
+        let subcommand = escape_label_value(subcommand);
+        for (bucket_count, bound) in histogram
+            .bucket_counts
+            .iter()
+            .zip(TASK_DURATION_BUCKETS.iter())
+        {
+            writeln!(
+                r,
+                "task_board_task_duration_seconds_bucket{{subcommand=\"{}\",le=\"{}\"}} {}",
+                subcommand, bound, bucket_count
+            )
+            .unwrap();
+        }
+        writeln!(
+            r,
+            "task_board_task_duration_seconds_bucket{{subcommand=\"{}\",le=\"+Inf\"}} {}",
+            subcommand, histogram.count
+        )
+        .unwrap();
+        writeln!(
+            r,
+            "task_board_task_duration_seconds_sum{{subcommand=\"{}\"}} {}",
+            subcommand, histogram.sum
+        )
+        .unwrap();
+        writeln!(
+            r,
+            "task_board_task_duration_seconds_count{{subcommand=\"{}\"}} {}",
+            subcommand, histogram.count
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        r,
+        "# HELP task_board_task_failures_total Count of task invocations with a non zero exit code."
+    )
+    .unwrap();
+    writeln!(r, "# TYPE task_board_task_failures_total counter").unwrap();
+    for ((subcommand, code), count) in &metrics.task_failures {
+        writeln!(
+            r,
+            "task_board_task_failures_total{{subcommand=\"{}\",code=\"{}\"}} {}",
+            escape_label_value(subcommand),
+            code,
+            count
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        r,
+        "# HELP task_board_http_requests_total Count of HTTP requests per route."
+    )
+    .unwrap();
+    writeln!(r, "# TYPE task_board_http_requests_total counter").unwrap();
+    for ((method, route, status), count) in &metrics.http_requests {
+        writeln!(
+            r,
+            "task_board_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+            method,
+            escape_label_value(route),
+            status,
+            count
+        )
+        .unwrap();
+    }
+
+    writeln!(r, "# HELP task_board_tasks Count of tasks per status.").unwrap();
+    writeln!(r, "# TYPE task_board_tasks gauge").unwrap();
+    for (status, count) in task_counts {
+        writeln!(r, "task_board_tasks{{status=\"{}\"}} {}", status, count).unwrap();
+    }
+
+    r
+}
@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+#[derive(serde::Serialize)]
+pub struct ProjectNode {
+    /// Full dotted project name
+    name: String,
+    /// Last component of project name
+    label: String,
+    depth: usize,
+    /// Filter matching tasks of this project and its subprojects
+    filter: String,
+    pending: u32,
+    completed: u32,
+    percent_complete: u32,
+}
+
+/// Build project tree with task counts including subprojects, flattened in depth first order
+pub fn project_tree(options: &RunOpts) -> anyhow::Result<Vec<ProjectNode>> {
+    let tasks = tw::export(
+        &["(", "status:pending", "or", "status:completed", ")"],
+        options,
+    )?;
+
+    // Key by name components, so that subprojects always sort right after their parent
+    let mut counts: BTreeMap<Vec<String>, (u32, u32)> = BTreeMap::new();
+    for task in &tasks {
+        let project = match &task.project {
+            Some(project) => project,
+            None => continue,
+        };
+        let components: Vec<String> = project.split('.').map(str::to_string).collect();
+        for depth in 1..=components.len() {
+            let count = counts.entry(components[..depth].to_vec()).or_default();
+            if task.status == "completed" {
+                count.1 += 1;
+            } else {
+                count.0 += 1;
+            }
+        }
+    }
+
+    Ok(counts
+        .into_iter()
+        .map(|(components, (pending, completed))| {
+            let name = components.join(".");
+            ProjectNode {
+                filter: format!("project:{}", name),
+                name,
+                label: components.last().unwrap().to_string(),
+                depth: components.len() - 1,
+                pending,
+                completed,
+                percent_complete: 100 * completed / (pending + completed),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,119 @@
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+#[derive(serde::Serialize)]
+struct RecurringChild {
+    uuid: String,
+    description: String,
+    status: String,
+    due: Option<String>,
+    /// Index of this instance in the parent mask
+    imask: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct RecurringTemplate {
+    uuid: String,
+    description: String,
+    modified: Option<String>,
+    recur: Option<String>,
+    due: Option<String>,
+    until: Option<String>,
+    /// Status of generated instances, `-` pending, `+` completed, `X` deleted, `W` waiting
+    mask: Option<String>,
+    children: Vec<RecurringChild>,
+}
+
+/// Modification of a recurring template
+#[derive(serde::Deserialize)]
+pub struct TemplateModification {
+    attribute: String,
+    value: String,
+    /// Also modify pending instances
+    #[serde(default)]
+    propagate: bool,
+}
+
+fn local_date(task: &tw::ExportedTask, name: &str) -> Option<String> {
+    let value = task.attribute(name)?;
+    Some(
+        tw::parse_date(&value)
+            .map(|d| crate::detail::format_local_date(&d))
+            .unwrap_or(value),
+    )
+}
+
+/// List recurring templates with their instances
+pub fn templates(options: &RunOpts) -> anyhow::Result<Vec<RecurringTemplate>> {
+    let mut children = tw::export(&["parent.any:"], options)?;
+    children.sort_by(|a, b| a.due.cmp(&b.due));
+
+    Ok(tw::export(&["status:recurring"], options)?
+        .into_iter()
+        .map(|t| RecurringTemplate {
+            children: children
+                .iter()
+                .filter(|c| c.attribute("parent").as_ref() == Some(&t.uuid))
+                .map(|c| RecurringChild {
+                    uuid: c.uuid.clone(),
+                    description: c.description.clone(),
+                    status: c.status.clone(),
+                    due: local_date(c, "due"),
+                    imask: c.attribute("imask"),
+                })
+                .collect(),
+            modified: t.modified.as_ref().map(crate::dates::timestamp),
+            recur: t.attribute("recur"),
+            due: local_date(&t, "due"),
+            until: local_date(&t, "until"),
+            mask: t.attribute("mask"),
+            description: t.description,
+            uuid: t.uuid,
+        })
+        .collect())
+}
+
+fn check_template(uuid: &str, options: &RunOpts) -> anyhow::Result<()> {
+    match tw::export(&[uuid], options)?.first() {
+        Some(t) if t.status == "recurring" => Ok(()),
+        _ => Err(anyhow::anyhow!("No recurring template with UUID {}", uuid)),
+    }
+}
+
+/// Modify a template, answering the propagation prompt with `rc.recurrence.confirmation`
+pub fn modify(
+    uuid: &str,
+    modification: &TemplateModification,
+    options: &RunOpts,
+) -> anyhow::Result<(i32, String)> {
+    check_template(uuid, options)?;
+    tw::validate_value(&modification.attribute, &modification.value, options)?;
+    let confirmation_arg = format!(
+        "rc.recurrence.confirmation:{}",
+        if modification.propagate { "yes" } else { "no" }
+    );
+    let modification_arg = format!("{}:{}", modification.attribute, modification.value);
+    tw::invoke_external(
+        &[
+            "rc.confirmation:off",
+            &confirmation_arg,
+            uuid,
+            "modify",
+            &modification_arg,
+        ],
+        options,
+    )
+}
+
+/// Stop recurrence by deleting the template, and optionally its pending instances
+pub fn stop(uuid: &str, delete_children: bool, options: &RunOpts) -> anyhow::Result<(i32, String)> {
+    check_template(uuid, options)?;
+    let confirmation_arg = format!(
+        "rc.recurrence.confirmation:{}",
+        if delete_children { "yes" } else { "no" }
+    );
+    tw::invoke_external(
+        &["rc.confirmation:off", &confirmation_arg, uuid, "delete"],
+        options,
+    )
+}
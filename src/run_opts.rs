@@ -1,4 +1,5 @@
 use structopt::StructOpt;
+use strum::VariantNames;
 
 /// Command line arguments
 #[derive(Default, StructOpt, Debug)]
@@ -12,10 +13,26 @@ pub struct RunOpts {
     #[structopt(default_value, short = "w", long = "width")]
     pub report_width: usize,
 
+    /// Stylesheet theme
+    #[structopt(long, default_value = "mvp", possible_values = crate::assets::Theme::VARIANTS)]
+    pub theme: crate::assets::Theme,
+
+    /// Additional stylesheet loaded after the theme, reloaded on each request
+    #[structopt(long, parse(from_os_str))]
+    pub user_css: Option<std::path::PathBuf>,
+
+    /// Load templates from this directory instead of embedded ones, reloading them on each request
+    #[structopt(long, parse(from_os_str))]
+    pub templates_dir: Option<std::path::PathBuf>,
+
     /// Task data dir, if non default
     #[structopt(skip)]
     pub task_data_dir: Option<std::ffi::OsString>,
 
+    /// Taskwarrior configuration file, if non default
+    #[structopt(skip)]
+    pub task_rc: Option<std::ffi::OsString>,
+
     /// Temporary test data dir, used for tests
     #[structopt(skip)]
     pub tmp_dir: Option<tempfile::TempDir>,
@@ -26,6 +43,9 @@ pub fn get_cl_opts() -> RunOpts {
     if let Some(task_data_dir) = std::env::var_os("TASKDATA") {
         opts.task_data_dir = Some(task_data_dir);
     }
+    if let Some(task_rc) = std::env::var_os("TASKRC") {
+        opts.task_rc = Some(task_rc);
+    }
     log::debug!("{:?}", opts);
     opts
 }
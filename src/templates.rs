@@ -0,0 +1,130 @@
+use rocket_contrib::templates::tera;
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "templates/"]
+struct EmbeddedTemplates;
+
+static TEMPLATE_EXTENSION: &str = ".html.tera";
+
+type Customizer = Box<dyn Fn(&mut tera::Tera) + Send + Sync>;
+
+/// Tera engine loaded with embedded templates, or templates from a directory
+pub struct Templates {
+    /// Directory overriding embedded templates, reloaded on each render
+    dir: Option<std::path::PathBuf>,
+    customizer: Customizer,
+    tera: tera::Tera,
+    /// Values available in all templates, unless the context sets them
+    globals: tera::Map<String, tera::Value>,
+}
+
+fn tera_error(e: tera::Error) -> anyhow::Error {
+    anyhow::anyhow!(e
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(": "))
+}
+
+fn load(dir: Option<&std::path::Path>, customizer: &Customizer) -> anyhow::Result<tera::Tera> {
+    let mut sources = std::collections::HashMap::new();
+    for filename in EmbeddedTemplates::iter() {
+        if let Some(name) = filename.strip_suffix(TEMPLATE_EXTENSION) {
+            let data = EmbeddedTemplates::get(&filename).unwrap();
+            sources.insert(name.to_string(), String::from_utf8(data.into_owned())?);
+        }
+    }
+    if let Some(dir) = dir {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let filename = path.file_name().and_then(|f| f.to_str()).unwrap_or("");
+            if let Some(name) = filename.strip_suffix(TEMPLATE_EXTENSION) {
+                sources.insert(name.to_string(), std::fs::read_to_string(&path)?);
+            }
+        }
+    }
+
+    let mut tera = tera::Tera::default();
+    // templates are registered without extension, all of them are HTML
+    tera.autoescape_on(vec![""]);
+    tera.add_raw_templates(
+        sources
+            .iter()
+            .map(|(name, source)| (name.as_str(), source.as_str()))
+            .collect(),
+    )
+    .map_err(tera_error)?;
+    customizer(&mut tera);
+    Ok(tera)
+}
+
+impl Templates {
+    pub fn new(
+        dir: Option<std::path::PathBuf>,
+        customizer: impl Fn(&mut tera::Tera) + Send + Sync + 'static,
+    ) -> anyhow::Result<Templates> {
+        let customizer: Customizer = Box::new(customizer);
+        let tera = load(dir.as_deref(), &customizer)?;
+        Ok(Templates {
+            dir,
+            customizer,
+            tera,
+            globals: tera::Map::new(),
+        })
+    }
+
+    pub fn global(
+        mut self,
+        name: &str,
+        value: &impl serde::Serialize,
+    ) -> anyhow::Result<Templates> {
+        self.globals
+            .insert(name.to_string(), tera::to_value(value)?);
+        Ok(self)
+    }
+
+    fn render(&self, name: &str, mut context: tera::Value) -> anyhow::Result<String> {
+        if let tera::Value::Object(context) = &mut context {
+            for (name, value) in &self.globals {
+                context.entry(name.clone()).or_insert_with(|| value.clone());
+            }
+        }
+        match &self.dir {
+            Some(dir) => load(Some(dir), &self.customizer)?.render(name, &context),
+            None => self.tera.render(name, &context),
+        }
+        .map_err(tera_error)
+    }
+}
+
+/// Template responder, rendered with the managed `Templates`
+pub struct Template {
+    name: &'static str,
+    context: anyhow::Result<tera::Value>,
+}
+
+impl Template {
+    pub fn render(name: &'static str, context: &impl serde::Serialize) -> Template {
+        Template {
+            name,
+            context: tera::to_value(context).map_err(anyhow::Error::from),
+        }
+    }
+}
+
+impl<'r> rocket::response::Responder<'r> for Template {
+    fn respond_to(self, request: &rocket::request::Request) -> rocket::response::Result<'r> {
+        let templates = request
+            .guard::<rocket::State<Templates>>()
+            .succeeded()
+            .ok_or(rocket::http::Status::InternalServerError)?;
+        let html = self
+            .context
+            .and_then(|context| templates.render(self.name, context))
+            .map_err(|e| {
+                log::error!("Failed to render template {:?}: {}", self.name, e);
+                rocket::http::Status::InternalServerError
+            })?;
+        rocket::response::content::Html(html).respond_to(request)
+    }
+}
@@ -4,6 +4,7 @@ fn test_data_dir() -> tempfile::TempDir {
         .prefix("test_task_data")
         .tempdir()
         .unwrap();
+    std::fs::File::create(data_dir.path().join("taskrc")).unwrap();
     let task_cmds = vec![
         vec!["add", "test"],
         vec!["add", "test2", "due:eom"],
@@ -16,6 +17,7 @@ fn test_data_dir() -> tempfile::TempDir {
         std::process::Command::new("task")
             .args(&task_cmd)
             .env("TASKDATA", data_dir.path())
+            .env("TASKRC", data_dir.path().join("taskrc"))
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null())
@@ -29,6 +31,7 @@ fn test_data_dir() -> tempfile::TempDir {
 fn run_opts(test_data_dir: tempfile::TempDir) -> crate::run_opts::RunOpts {
     let mut opts = crate::run_opts::get_default_opts();
     opts.task_data_dir = Some(test_data_dir.path().as_os_str().to_os_string());
+    opts.task_rc = Some(test_data_dir.path().join("taskrc").into_os_string());
     opts.tmp_dir = Some(test_data_dir);
     opts
 }
@@ -40,8 +43,14 @@ fn run_opts_low_report_width(run_opts: crate::run_opts::RunOpts) -> crate::run_o
     run_opts
 }
 
+lazy_static! {
+    /// Held by tests changing UDAs and while building rocket instances, since column types are global
+    static ref COLUMN_TYPES_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+}
+
 #[rstest::fixture]
 fn rocket_client(run_opts: crate::run_opts::RunOpts) -> rocket::local::Client {
+    let _lock = COLUMN_TYPES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     rocket::local::Client::new(super::rocket(run_opts)).unwrap()
 }
 
@@ -52,6 +61,23 @@ fn rocket_client_low_report_width(
     rocket_client(run_opts_low_report_width)
 }
 
+/// Options of the tested rocket instance
+fn client_options(rocket_client: &rocket::local::Client) -> &crate::run_opts::RunOpts {
+    rocket_client
+        .rocket()
+        .state::<crate::run_opts::RunOpts>()
+        .unwrap()
+}
+
+/// Task with exactly this description
+fn task_by_description(
+    description: &str,
+    options: &crate::run_opts::RunOpts,
+) -> crate::tw::ExportedTask {
+    let filter = format!("description.is:{}", description);
+    crate::tw::export(&[&filter], options).unwrap().remove(0)
+}
+
 #[rstest::rstest]
 fn test_report_default(rocket_client: rocket::local::Client) {
     let mut response = rocket_client.get("/").dispatch();
@@ -128,4 +154,1101 @@ fn test_asset(rocket_client: rocket::local::Client) {
     assert!(response.body_bytes().unwrap().len() > 0);
 }
 
+#[rstest::rstest]
+fn test_asset_caching(rocket_client: rocket::local::Client) {
+    let response = rocket_client.get("/static/js/app.js").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.headers().get_one("Cache-Control"),
+        Some("no-cache")
+    );
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+    let response = rocket_client
+        .get("/static/js/app.js")
+        .header(rocket::http::Header::new("If-None-Match", etag.clone()))
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::NotModified);
+
+    let mut response = rocket_client
+        .get(crate::assets::url("js/app.js"))
+        .header(rocket::http::Header::new(
+            "Accept-Encoding",
+            "gzip, deflate, br;q=0",
+        ))
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.headers().get_one("Cache-Control"),
+        Some("public, max-age=31536000, immutable")
+    );
+    assert_eq!(response.headers().get_one("Content-Encoding"), Some("gzip"));
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::JavaScript)
+    );
+    assert_ne!(response.headers().get_one("ETag").unwrap(), etag);
+    let data = response.body_bytes().unwrap();
+    let mut decoded = String::new();
+    std::io::Read::read_to_string(&mut flate2::read::GzDecoder::new(&data[..]), &mut decoded)
+        .unwrap();
+    assert!(decoded.contains(&"edit.init();"));
+
+    let response = rocket_client
+        .get("/static/0123456789abcdef/js/app.js")
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.headers().get_one("Cache-Control"),
+        Some("no-cache")
+    );
+
+    let response = rocket_client.get("/static/../Cargo.toml").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::NotFound);
+}
+
+#[rstest::rstest]
+fn test_metrics(rocket_client: rocket::local::Client) {
+    rocket_client.get("/").dispatch();
+    rocket_client
+        .post("/shell")
+        .body("\"qsdfmlkjhgfwxcv\"")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+
+    let mut response = rocket_client.get("/metrics").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(
+        body.contains("task_board_task_duration_seconds_bucket{subcommand=\"next\",le=\"+Inf\"}")
+    );
+    assert!(
+        body.contains("task_board_http_requests_total{method=\"GET\",route=\"/\",status=\"200\"}")
+    );
+    assert!(body.contains("task_board_tasks{status=\"pending\"} 3"));
+    assert!(body.contains("task_board_task_failures_total{subcommand=\"other\",code=\"1\"}"));
+    assert!(!body.contains("qsdfmlkjhgfwxcv"));
+}
+
+#[rstest::rstest]
+fn test_board(rocket_client: rocket::local::Client) {
+    let mut response = rocket_client.get("/board").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::HTML)
+    );
+    assert!(response.body_string().unwrap().contains(&"data-uuid="));
+
+    let response = rocket_client.get("/board?group=project").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+
+    let response = rocket_client.get("/board?group=description").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+}
+
+#[rstest::rstest]
+fn test_board_move(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let tasks = crate::tw::export(&["status:pending"], options).unwrap();
+    let uuid = &tasks[0].uuid;
+    let description = &tasks[0].description;
+
+    let mut response = rocket_client
+        .post("/board/project")
+        .body(format!("{{\"uuid\":\"{}\",\"value\":\"board\"}}", uuid))
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert!(response.body_string().unwrap().ends_with(",\"code\":0}"));
+
+    let tasks = crate::tw::export(&[uuid.as_str()], options).unwrap();
+    assert_eq!(tasks[0].project, Some("board".to_string()));
+
+    for attribute in &["description", "rc.data.location"] {
+        let response = rocket_client
+            .post(format!("/board/{}", attribute))
+            .body(format!("{{\"uuid\":\"{}\",\"value\":\"board\"}}", uuid))
+            .header(rocket::http::ContentType::JSON)
+            .dispatch();
+        assert_eq!(response.status(), rocket::http::Status::BadRequest);
+    }
+    let edited = crate::tw::export(&[uuid.as_str()], options).unwrap();
+    assert_eq!(&edited[0].description, description);
+}
+
+#[rstest::rstest]
+fn test_calendar(rocket_client: rocket::local::Client) {
+    let mut response = rocket_client.get("/calendar").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::HTML)
+    );
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"data-date="));
+    assert!(body.contains(&"test2"));
+
+    let mut response = rocket_client.get("/calendar?month=2020-02").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"data-date=\"2020-02-29\""));
+    assert!(!body.contains(&"test2"));
+
+    let response = rocket_client.get("/calendar?month=2020-13").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+}
+
+#[rstest::rstest]
+fn test_charts(rocket_client: rocket::local::Client) {
+    for url in &[
+        "/charts/burndown",
+        "/charts/burndown?period=weekly",
+        "/charts/history",
+        "/charts/history?period=monthly",
+    ] {
+        let mut response = rocket_client.get(*url).dispatch();
+        assert_eq!(response.status(), rocket::http::Status::Ok);
+        assert_eq!(
+            response.content_type(),
+            Some(rocket::http::ContentType::HTML)
+        );
+        assert!(response.body_string().unwrap().contains(&"<svg"));
+    }
+
+    let response = rocket_client
+        .get("/charts/history?period=hourly")
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+}
+
+#[rstest::rstest]
+fn test_charts_history_svg(run_opts: crate::run_opts::RunOpts) {
+    let tasks = crate::tw::export(&[], &run_opts).unwrap();
+    let history = crate::charts::history(&tasks, crate::charts::Period::Daily);
+    assert_eq!(history.len(), 1);
+    assert!(crate::charts::history_svg(&history).contains(&"added: 3</title>"));
+}
+
+#[rstest::rstest]
+fn test_charts_history_future(run_opts: crate::run_opts::RunOpts) {
+    let mut tasks = crate::tw::export(&[], &run_opts).unwrap();
+    let future = chrono::Utc::now() + chrono::Duration::days(400);
+    tasks[0].end = Some(future);
+    tasks[0].status = "completed".to_string();
+    tasks[1].entry = Some(future);
+    let history = crate::charts::history(&tasks, crate::charts::Period::Daily);
+    assert_eq!(history.len(), 1);
+    let svg = crate::charts::history_svg(&history);
+    assert!(svg.contains(&"added: 3</title>"));
+    assert!(svg.contains(&"completed: 1</title>"));
+
+    for task in &mut tasks {
+        task.entry = Some(future);
+    }
+    assert!(crate::charts::history(&tasks, crate::charts::Period::Daily).is_empty());
+}
+
+#[rstest::rstest]
+fn test_projects(rocket_client: rocket::local::Client) {
+    rocket_client
+        .post("/shell")
+        .body("\"add projtest project:work.client.api\"")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+
+    let mut response = rocket_client.get("/projects").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::HTML)
+    );
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"data-project=\"work\""));
+    assert!(body.contains(&"data-project=\"work.client\""));
+    assert!(body.contains(&"data-project=\"work.client.api\""));
+
+    let mut response = rocket_client
+        .get("/next?filter=project%3Awork.client")
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"projtest"));
+    assert!(!body.contains(&"test2"));
+}
+
+#[rstest::rstest]
+fn test_task(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let task = task_by_description("test2", options);
+    let uuid = &task.uuid;
+
+    let mut response = rocket_client
+        .post(format!("/task/{}", uuid))
+        .body("{\"attribute\":\"project\",\"value\":\"detail\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert!(response.body_string().unwrap().ends_with(",\"code\":0}"));
+
+    let mut response = rocket_client.get(format!("/task/{}", uuid)).dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::HTML)
+    );
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"data-attribute=\"due\""));
+    assert!(body.contains(&"<ins>detail</ins>"));
+
+    let response = rocket_client
+        .get("/task/00000000-0000-0000-0000-000000000000")
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+}
+
+#[rstest::rstest]
+fn test_task_history_malformed(run_opts: crate::run_opts::RunOpts) {
+    let task = task_by_description("test2", &run_opts);
+    let undo_filepath = crate::tw::data_location(&run_opts)
+        .unwrap()
+        .join("undo.data");
+    let mut undo_data = std::fs::read_to_string(&undo_filepath).unwrap();
+    undo_data.push_str("time 1600000000\nnew [description:\"unterminated\n---\n");
+    std::fs::write(&undo_filepath, undo_data).unwrap();
+
+    let history = crate::undo::task_history(&task.uuid, &run_opts)
+        .unwrap()
+        .unwrap();
+    assert_eq!(history.len(), 1);
+}
+
+#[test]
+fn test_parse_ff4() {
+    let task = crate::undo::parse_ff4(
+        r#"[description:"a &open;b&close; \"c\"" entry:"1600000000" uuid:"abc"]"#,
+    )
+    .unwrap();
+    assert_eq!(task["description"], "a [b] \"c\"");
+    assert_eq!(task["entry"], "1600000000");
+    assert_eq!(task["uuid"], "abc");
+}
+
+#[rstest::rstest]
+fn test_graph(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let blocked = task_by_description("test2", options);
+    let blocking = task_by_description("test", options);
+    let depends_arg = format!("depends:{}", blocking.uuid);
+    crate::tw::invoke_external(&[&blocked.uuid, "modify", &depends_arg], options).unwrap();
+
+    let mut response = rocket_client.get("/graph").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::HTML)
+    );
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"<svg"));
+    assert!(body.contains(&format!("href=\"/task/{}\"", blocked.uuid)));
+    assert!(body.contains(&"Blocked chains"));
+    assert!(!body.contains(&"Cycles"));
+
+    let mut response = rocket_client.get("/graph?format=dot").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert!(response
+        .body_string()
+        .unwrap()
+        .contains(&format!("\"{}\" -> \"{}\"", blocked.uuid, blocking.uuid)));
+}
+
+#[rstest::rstest]
+fn test_bulk(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let tasks = crate::tw::export(&["status:pending"], options).unwrap();
+    let uuids: Vec<String> = tasks.iter().map(|t| format!("\"{}\"", t.uuid)).collect();
+
+    let mut response = rocket_client
+        .post("/bulk")
+        .body(format!(
+            "{{\"uuids\":[{}],\"action\":\"tag\",\"value\":\"bulk\",\"preview\":true}}",
+            uuids.join(",")
+        ))
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"\"dry_run\":true"));
+    assert!(body.contains(&"\"modify\",\"+bulk\"]"));
+    assert!(!body.contains(&"\"changed\":true"));
+    assert_eq!(crate::tw::count(&["+bulk"], options).unwrap(), 0);
+
+    let mut response = rocket_client
+        .post("/bulk")
+        .body(format!(
+            "{{\"uuids\":[{}],\"action\":\"tag\",\"value\":\"bulk\"}}",
+            uuids.join(",")
+        ))
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"\"dry_run\":false"));
+    assert!(!body.contains(&"\"changed\":false"));
+    assert_eq!(crate::tw::count(&["+bulk"], options).unwrap(), 3);
+
+    for uuid in &["", "1", "1-5", "0123456789abcdef"] {
+        let response = rocket_client
+            .post("/bulk")
+            .body(format!("{{\"uuids\":[\"{}\"],\"action\":\"done\"}}", uuid))
+            .header(rocket::http::ContentType::JSON)
+            .dispatch();
+        assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+    }
+    assert_eq!(crate::tw::count(&["status:pending"], options).unwrap(), 3);
+}
+
+#[rstest::rstest]
+fn test_annotations(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let task = task_by_description("test2", options);
+    let url = format!("/task/{}/annotations", task.uuid);
+
+    let response = rocket_client
+        .post(url.clone())
+        .body("{\"description\":\"line 1\\nline 2\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+
+    let mut response = rocket_client.get(url.clone()).dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert!(response
+        .body_string()
+        .unwrap()
+        .contains(&"\"description\":\"line 1\\nline 2\""));
+
+    let mut response = rocket_client.get("/").dispatch();
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"1 annotation(s)"));
+    assert!(body.contains(&"line 1\nline 2"));
+    assert_eq!(body.matches("<tr class=\"annotations\"").count(), 1);
+    let entry = crate::tw::annotations(&task.uuid, options).unwrap()[0].entry;
+
+    let response = rocket_client
+        .put(url.clone())
+        .body("{\"old\":\"line 1\\nline 2\",\"description\":\"edited\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let annotations = crate::tw::annotations(&task.uuid, options).unwrap();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(annotations[0].description, "edited");
+    assert!(annotations[0].entry.is_some());
+    assert_eq!(annotations[0].entry, entry);
+
+    let response = rocket_client
+        .put(url.clone())
+        .body("{\"old\":\"missing\",\"description\":\"lost\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+    assert_eq!(
+        crate::tw::annotations(&task.uuid, options).unwrap(),
+        annotations
+    );
+
+    let response = rocket_client
+        .delete(url)
+        .body("{\"description\":\"edited\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert!(crate::tw::annotations(&task.uuid, options)
+        .unwrap()
+        .is_empty());
+}
+
+#[rstest::rstest]
+fn test_report_overrides(rocket_client: rocket::local::Client) {
+    let mut response = rocket_client
+        .get("/next?filter=test2&sort=due%2B&columns=id,description.count,due")
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"test2"));
+    assert!(!body.contains(&"0123456789"));
+    assert!(body.contains(&"<th>Due</th>"));
+    assert!(!body.contains(&"<th>Urg</th>"));
+}
+
+#[rstest::rstest]
+fn test_save_view(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+
+    let response = rocket_client
+        .post("/views")
+        .body("{\"name\":\"board\",\"report\":\"next\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+
+    let response = rocket_client
+        .post("/views")
+        .body(
+            "{\"name\":\"mine\",\"description\":\"My view\",\"report\":\"next\",\
+             \"filter\":\"test2\",\"columns\":\"id,description,due\"}",
+        )
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let definition = crate::views::report_definition("mine", options).unwrap();
+    assert_eq!(definition.description, "My view");
+    assert_eq!(definition.columns, vec!["id", "description", "due"]);
+    assert!(definition.filter.ends_with("( test2 )"));
+
+    let mut response = rocket_client.get("/mine").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"test2"));
+    assert!(!body.contains(&"0123456789"));
+    assert!(body.contains(&"<option value=\"mine\" title=\"My view\" selected>"));
+
+    for view in &[
+        "{\"name\":\"next\",\"report\":\"list\"}",
+        "{\"name\":\"mine\",\"report\":\"list\"}",
+    ] {
+        let response = rocket_client
+            .post("/views")
+            .body(*view)
+            .header(rocket::http::ContentType::JSON)
+            .dispatch();
+        assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+    }
+    assert_eq!(
+        crate::views::report_definition("mine", options)
+            .unwrap()
+            .description,
+        "My view"
+    );
+
+    let response = rocket_client
+        .post("/views")
+        .body("{\"name\":\"mine\",\"report\":\"list\",\"overwrite\":true}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_ne!(
+        crate::views::report_definition("mine", options)
+            .unwrap()
+            .description,
+        "My view"
+    );
+}
+
+#[rstest::rstest]
+fn test_report_editor(rocket_client: rocket::local::Client) {
+    let mut response = rocket_client.get("/reports?name=next").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"<option value=\"due.relative\">"));
+
+    let mut response = rocket_client
+        .post("/reports")
+        .body(
+            "{\"name\":\"sprint\",\"columns\":[\"id\",\"nope.relative\"],\"labels\":[],\
+             \"preview\":true}",
+        )
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"Unknown column \\\"nope.relative\\\""));
+    assert!(body.contains(&"\"report\":null"));
+
+    let definition = "\"columns\":[\"id\",\"description.count\",\"due.relative\"],\
+                      \"labels\":[\"ID\",\"Task\",\"When\"],\"sort\":\"due+\",\
+                      \"filter\":\"status:pending due.any:\"";
+    let mut response = rocket_client
+        .post("/reports")
+        .body(format!(
+            "{{\"name\":\"sprint\",{},\"preview\":true}}",
+            definition
+        ))
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"\"errors\":[]"));
+    assert!(body.contains(&"\"labels\":[\"ID\",\"Task\",\"When\"]"));
+    assert!(body.contains(&"test2"));
+    assert!(!body.contains(&"0123456789"));
+
+    let response = rocket_client
+        .post("/reports")
+        .body(format!("{{\"name\":\"sprint\",{}}}", definition))
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let options = client_options(&rocket_client);
+    let saved = crate::views::report_definition("sprint", options).unwrap();
+    assert_eq!(saved.labels, vec!["ID", "Task", "When"]);
+    assert_eq!(saved.filter, "status:pending due.any:");
+}
+
+#[rstest::rstest]
+fn test_udas(rocket_client: rocket::local::Client) {
+    let _lock = COLUMN_TYPES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let options = client_options(&rocket_client);
+
+    let response = rocket_client
+        .post("/udas")
+        .body("{\"name\":\"due\",\"type\":\"date\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+
+    let response = rocket_client
+        .post("/udas")
+        .body("{\"name\":\"estimate\",\"type\":\"numeric\",\"label\":\"Estimate\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert!(crate::tw::column_type("estimate").is_some());
+
+    let task = task_by_description("test2", options);
+    let response = rocket_client
+        .post(format!("/task/{}", task.uuid))
+        .body("{\"attribute\":\"estimate\",\"value\":\"3\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+
+    let mut response = rocket_client.get("/udas").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"data-name=\"estimate\" data-type=\"numeric\" data-label=\"Estimate\""));
+    assert!(body.contains(&"<a href=\"/all?filter=estimate.any%3A\">1</a>"));
+}
+
+#[rstest::rstest]
+fn test_column_types_at_ignite(run_opts: crate::run_opts::RunOpts) {
+    crate::tw::config_set("uda.estimate.type", "duration", &run_opts).unwrap();
+    let _lock = COLUMN_TYPES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let _rocket = super::rocket(run_opts);
+
+    assert_eq!(
+        crate::tw::column_type("estimate").unwrap().type_,
+        crate::tw::AttributeType::Duration
+    );
+}
+
+#[rstest::rstest]
+fn test_uda_types(rocket_client: rocket::local::Client) {
+    let _lock = COLUMN_TYPES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let options = client_options(&rocket_client);
+    crate::tw::config_set("uda.size.type", "numeric", options).unwrap();
+    crate::tw::config_set("uda.spent.type", "duration", options).unwrap();
+    crate::tw::config_set("uda.color.type", "string", options).unwrap();
+    crate::tw::config_set("uda.color.values", "red,blue", options).unwrap();
+    crate::tw::refresh_column_types(options).unwrap();
+
+    let type_of = |name| crate::tw::column_type(name).unwrap().type_;
+    assert_eq!(type_of("size"), crate::tw::AttributeType::Numeric);
+    assert_eq!(type_of("spent"), crate::tw::AttributeType::Duration);
+    assert_eq!(type_of("recur"), crate::tw::AttributeType::Duration);
+    assert_eq!(type_of("color"), crate::tw::AttributeType::String);
+
+    let task = task_by_description("test2", options);
+    let url = format!("/task/{}", task.uuid);
+    for (attribute, value, status) in &[
+        ("size", "abc", rocket::http::Status::InternalServerError),
+        ("size", "2.5", rocket::http::Status::Ok),
+        ("color", "green", rocket::http::Status::InternalServerError),
+        ("color", "red", rocket::http::Status::Ok),
+        ("spent", "PT2H", rocket::http::Status::Ok),
+    ] {
+        let response = rocket_client
+            .post(url.clone())
+            .body(format!(
+                "{{\"attribute\":\"{}\",\"value\":\"{}\"}}",
+                attribute, value
+            ))
+            .header(rocket::http::ContentType::JSON)
+            .dispatch();
+        assert_eq!(response.status(), *status);
+    }
+
+    let mut response = rocket_client.get(url).dispatch();
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"class=\"num\" data-attribute=\"size\""));
+    assert!(body.contains(&"class=\"dur\" data-attribute=\"spent\""));
+
+    crate::tw::config_set("uda.size.type", "", options).unwrap();
+    crate::tw::refresh_column_types(options).unwrap();
+    assert!(crate::tw::column_type("size").is_none());
+}
+
+#[rstest::rstest]
+fn test_typed_edit(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+
+    let mut response = rocket_client.get("/calc?expression=eom").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"\"expression\":\"eom\""));
+    assert!(body.contains(&"T23:59:59\""));
+
+    let mut response = rocket_client.get("/next").dispatch();
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"data-attribute=\"project\""));
+    assert!(body.contains(&"data-attribute=\"description\""));
+
+    let task = task_by_description("test2", options);
+    let url = format!("/task/{}", task.uuid);
+    for (attribute, value, status) in &[
+        ("due", "notadate", rocket::http::Status::InternalServerError),
+        ("due", "eoy", rocket::http::Status::Ok),
+        ("tags", "chip", rocket::http::Status::InternalServerError),
+        ("tags", "", rocket::http::Status::InternalServerError),
+        ("bogus", "", rocket::http::Status::InternalServerError),
+        ("uuid", "", rocket::http::Status::InternalServerError),
+        ("tags", "+chip", rocket::http::Status::Ok),
+        ("tags", "+other", rocket::http::Status::Ok),
+        ("tags", "-chip", rocket::http::Status::Ok),
+    ] {
+        let response = rocket_client
+            .post(url.clone())
+            .body(format!(
+                "{{\"attribute\":\"{}\",\"value\":\"{}\"}}",
+                attribute, value
+            ))
+            .header(rocket::http::ContentType::JSON)
+            .dispatch();
+        assert_eq!(response.status(), *status);
+    }
+    let edited = crate::tw::export(&[&task.uuid], options).unwrap().remove(0);
+    assert_eq!(edited.tags, vec!["other"]);
+    assert_ne!(edited.due, task.due);
+}
+
+#[rstest::rstest]
+fn test_dates(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    assert_eq!(
+        crate::dates::parse_iso("20201231T120000Z").unwrap(),
+        crate::dates::parse_iso("2020-12-31T12:00:00Z").unwrap()
+    );
+    assert!(crate::dates::parse_iso("2020-12-31").is_err());
+    assert!(crate::dates::resolve("notadate", options).is_err());
+
+    let task = task_by_description("test2", options);
+    let due = task.due.unwrap();
+    assert_eq!(crate::dates::resolve("eom", options).unwrap(), due);
+
+    let mut response = rocket_client.get("/next").dispatch();
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&format!("data-ts=\"{}\"", crate::dates::timestamp(&due))));
+}
+
+#[rstest::rstest]
+fn test_recurring(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    crate::tw::invoke_external(
+        &["add", "recurring", "recur:weekly", "due:tomorrow"],
+        options,
+    )
+    .unwrap();
+    let template = crate::tw::export(&["status:recurring"], options)
+        .unwrap()
+        .remove(0);
+    let url = format!("/templates/recurring/{}", template.uuid);
+
+    let mut response = rocket_client.get("/templates/recurring").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&format!("data-uuid=\"{}\"", template.uuid)));
+    assert!(body.contains(&"<dd>weekly</dd>"));
+
+    // taskwarrior report
+    let mut response = rocket_client.get("/recurring").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert!(response.body_string().unwrap().contains(&"<table>"));
+
+    let task = task_by_description("test2", options);
+    let response = rocket_client
+        .post(format!("/templates/recurring/{}", task.uuid))
+        .body("{\"attribute\":\"description\",\"value\":\"nope\"}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::InternalServerError);
+
+    let response = rocket_client
+        .post(url.clone())
+        .body("{\"attribute\":\"recur\",\"value\":\"monthly\",\"propagate\":true}")
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let template = crate::tw::export(&[&template.uuid], options)
+        .unwrap()
+        .remove(0);
+    assert_eq!(template.attribute("recur").unwrap(), "monthly");
+
+    let response = rocket_client
+        .delete(format!("{}?delete_children=true", url))
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(crate::tw::count(&["status:recurring"], options).unwrap(), 0);
+}
+
+#[rstest::rstest]
+fn test_offline(rocket_client: rocket::local::Client) {
+    let mut response = rocket_client.get("/sw.js").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::JavaScript)
+    );
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"\"fetch\""));
+    assert!(body.contains(&format!("\"task-board-{}\"", *crate::assets::VERSION)));
+
+    let response = rocket_client.get("/static/manifest.json").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+
+    let mut response = rocket_client.get("/").dispatch();
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"<link rel=\"manifest\""));
+    assert!(body.contains(&"data-modified=\""));
+}
+
+#[rstest::rstest]
+fn test_unmodified_since(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let task = task_by_description("test2", options);
+    let url = format!("/task/{}", task.uuid);
+    let body = "{\"attribute\":\"project\",\"value\":\"sync\"}";
+    let later =
+        httpdate::fmt_http_date(std::time::SystemTime::now() + std::time::Duration::from_secs(60));
+    for (since, status) in &[
+        ("yesterday", rocket::http::Status::BadRequest),
+        (
+            "Thu, 01 Jan 1970 00:00:00 GMT",
+            rocket::http::Status::Conflict,
+        ),
+        (later.as_str(), rocket::http::Status::Ok),
+    ] {
+        let response = rocket_client
+            .post(url.clone())
+            .body(body)
+            .header(rocket::http::ContentType::JSON)
+            .header(rocket::http::Header::new(
+                "If-Unmodified-Since",
+                since.to_string(),
+            ))
+            .dispatch();
+        assert_eq!(response.status(), *status);
+    }
+    assert_eq!(crate::tw::count(&["project:sync"], options).unwrap(), 1);
+}
+
+#[rstest::rstest]
+fn test_conflict_changes(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let task = task_by_description("test2", options);
+    let modified = task.modified.unwrap();
+    // modification times have a resolution of one second
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    crate::tw::invoke_external(&[&task.uuid, "modify", "priority:H"], options).unwrap();
+
+    let mut response = rocket_client
+        .post(format!("/task/{}", task.uuid))
+        .body("{\"attribute\":\"project\",\"value\":\"sync\"}")
+        .header(rocket::http::ContentType::JSON)
+        .header(rocket::http::Header::new(
+            "If-Unmodified-Since",
+            httpdate::fmt_http_date(modified.into()),
+        ))
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Conflict);
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&"\"attribute\":\"priority\",\"old\":null,\"new\":\"H\""));
+    assert!(!body.contains(&"\"attribute\":\"description\""));
+
+    let mut response = rocket_client
+        .post("/bulk")
+        .body(format!(
+            "{{\"uuids\":[\"{0}\"],\"action\":\"tag\",\"value\":\"bulk\",\"modified\":{{\"{0}\":\"{1}\"}}}}",
+            task.uuid,
+            crate::dates::timestamp(&modified)
+        ))
+        .header(rocket::http::ContentType::JSON)
+        .dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Conflict);
+    assert!(response.body_string().unwrap().contains(&task.uuid));
+    assert_eq!(crate::tw::count(&["+bulk"], options).unwrap(), 0);
+    assert_eq!(crate::tw::count(&["project:sync"], options).unwrap(), 0);
+}
+
+#[rstest::rstest]
+fn test_templates_dir(run_opts: crate::run_opts::RunOpts) {
+    let templates_dir = tempfile::Builder::new()
+        .prefix("test_templates")
+        .tempdir()
+        .unwrap();
+    let template_path = templates_dir.path().join("projects.html.tera");
+    std::fs::write(&template_path, "<p>{{ title }} custom</p>").unwrap();
+    let mut run_opts = run_opts;
+    run_opts.templates_dir = Some(templates_dir.path().to_path_buf());
+    let rocket_client = rocket_client(run_opts);
+
+    let mut response = rocket_client.get("/projects").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::HTML)
+    );
+    assert!(response.body_string().unwrap().ends_with(" custom</p>"));
+
+    // other templates are still embedded, and changes are picked without restart
+    std::fs::write(&template_path, "{% extends \"layout\" %}").unwrap();
+    let mut response = rocket_client.get("/projects").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert!(response.body_string().unwrap().contains("<html"));
+}
+
+#[rstest::rstest]
+fn test_no_external_scripts(rocket_client: rocket::local::Client) {
+    let url_attributes = ["src=\"", "href=\"", "action=\""];
+    for page in &[
+        "/",
+        "/board",
+        "/calendar",
+        "/graph",
+        "/projects",
+        "/reports",
+        "/templates/recurring",
+        "/udas",
+    ] {
+        let mut response = rocket_client.get(*page).dispatch();
+        assert_eq!(response.status(), rocket::http::Status::Ok);
+        let body = response.body_string().unwrap();
+        assert!(!body.to_lowercase().contains(&"jquery"), "{}", page);
+        for attribute in &url_attributes {
+            for url in body.split(attribute).skip(1) {
+                assert!(
+                    url.starts_with('/') && !url.starts_with("//"),
+                    "External URL in {}: {}",
+                    page,
+                    url.split('"').next().unwrap()
+                );
+            }
+        }
+        assert!(body.contains(&format!(
+            "<script type=\"module\" src=\"{}\">",
+            crate::assets::url("js/app.js")
+        )));
+    }
+
+    // modules only import each other
+    for filepath in crate::assets::iter().filter(|f| f.starts_with("js/") && f.ends_with(".js")) {
+        let mut response = rocket_client
+            .get(format!("/static/{}", filepath))
+            .dispatch();
+        assert_eq!(response.status(), rocket::http::Status::Ok);
+        assert_eq!(
+            response.content_type(),
+            Some(rocket::http::ContentType::JavaScript)
+        );
+        let source = response.body_string().unwrap();
+        assert!(!source.to_lowercase().contains(&"jquery"), "{}", filepath);
+        for line in source.lines().filter(|l| l.starts_with("import ")) {
+            assert!(line.contains(&"from \"./"), "{}: {}", filepath, line);
+        }
+    }
+}
+
+#[rstest::rstest]
+fn test_updates(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let version = |rocket_client: &rocket::local::Client| {
+        let mut response = rocket_client.get("/updates").dispatch();
+        assert_eq!(response.status(), rocket::http::Status::Ok);
+        assert_eq!(
+            response.content_type(),
+            Some(rocket::http::ContentType::JSON)
+        );
+        response.body_string().unwrap()
+    };
+
+    let before = version(&rocket_client);
+    assert_eq!(version(&rocket_client), before);
+    crate::tw::invoke_external(&["add", "live"], options).unwrap();
+    assert_ne!(version(&rocket_client), before);
+}
+
+#[rstest::rstest]
+fn test_theme(run_opts: crate::run_opts::RunOpts) {
+    let user_css = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(user_css.path(), "body { margin: 0 }").unwrap();
+    let mut run_opts = run_opts;
+    run_opts.theme = crate::assets::Theme::Water;
+    run_opts.user_css = Some(user_css.path().to_path_buf());
+    let rocket_client = rocket_client(run_opts);
+
+    let mut response = rocket_client.get("/").dispatch();
+    let body = response.body_string().unwrap();
+    assert!(body.contains(&crate::assets::url("water.css")));
+    assert!(!body.contains(&crate::assets::url("mvp.css")));
+    assert!(body.contains(&"<link rel=\"stylesheet\" href=\"/user.css\">"));
+
+    let mut response = rocket_client.get("/user.css").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::CSS)
+    );
+    assert_eq!(response.body_string().unwrap(), "body { margin: 0 }");
+}
+
+#[rstest::rstest]
+fn test_color_rules(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let mut response = rocket_client.get("/user.css").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::NotFound);
+    assert!(!rocket_client
+        .get("/")
+        .dispatch()
+        .body_string()
+        .unwrap()
+        .contains(&"href=\"/user.css\""));
+
+    let test = task_by_description("test", options);
+    let test2 = task_by_description("test2", options);
+    crate::tw::invoke_external(&[&test.uuid, "start"], options).unwrap();
+    crate::tw::invoke_external(&[&test.uuid, "modify", "due:yesterday"], options).unwrap();
+    crate::tw::invoke_external(
+        &[&test2.uuid, "modify", &format!("depends:{}", test.uuid)],
+        options,
+    )
+    .unwrap();
+
+    response = rocket_client.get("/").dispatch();
+    let body = response.body_string().unwrap();
+    let classes = row_attribute(&body, &test.uuid, "class");
+    for class in &["color-active", "color-blocking", "color-overdue"] {
+        assert!(classes.split(' ').any(|c| c == *class), "{}", classes);
+    }
+    let classes = row_attribute(&body, &test2.uuid, "class");
+    assert!(classes.split(' ').any(|c| c == "color-blocked"));
+    assert!(!classes.split(' ').any(|c| c == "color-active"));
+}
+
+/// Attribute of the report row of a task
+fn row_attribute(body: &str, uuid: &str, attribute: &str) -> String {
+    let row = body
+        .split(&format!("<tr data-uuid=\"{}\"", uuid))
+        .nth(1)
+        .unwrap();
+    let row = &row[..row.find('>').unwrap()];
+    row.split(&format!(" {}=\"", attribute))
+        .nth(1)
+        .map(|v| v[..v.find('"').unwrap()].to_string())
+        .unwrap_or_default()
+}
+
+#[rstest::rstest]
+fn test_color_style(rocket_client: rocket::local::Client) {
+    let options = client_options(&rocket_client);
+    let test = task_by_description("test", options);
+    let test2 = task_by_description("test2", options);
+    crate::tw::invoke_external(&[&test.uuid, "start"], options).unwrap();
+    crate::tw::invoke_external(&[&test.uuid, "modify", "due:yesterday"], options).unwrap();
+    crate::tw::invoke_external(&[&test2.uuid, "modify", "+urgent"], options).unwrap();
+    crate::tw::config_set("color.active", "white on red", options).unwrap();
+    crate::tw::config_set("color.overdue", "bold blue", options).unwrap();
+    crate::tw::config_set("color.tag.urgent", "on rgb500", options).unwrap();
+
+    let mut response = rocket_client.get("/").dispatch();
+    let body = response.body_string().unwrap();
+    // active has precedence over overdue, whose bold attribute is kept
+    assert_eq!(
+        row_attribute(&body, &test.uuid, "style"),
+        "--task-fg-light: #c0c0c0; --task-fg-dark: #c0c0c0; --task-bg: #800000; \
+         --task-font-weight: bold"
+    );
+    assert!(row_attribute(&body, &test2.uuid, "class")
+        .split(' ')
+        .any(|c| c == "color-tag-urgent"));
+    // readable foreground on a red background
+    assert_eq!(
+        row_attribute(&body, &test2.uuid, "style"),
+        "--task-fg-light: #ffffff; --task-fg-dark: #ffffff; --task-bg: #ff0000"
+    );
+
+    crate::tw::config_set("rule.precedence.color", "overdue,active", options).unwrap();
+    let mut response = rocket_client.get("/").dispatch();
+    let body = response.body_string().unwrap();
+    assert_eq!(
+        row_attribute(&body, &test.uuid, "style"),
+        "--task-fg-light: #000080; --task-fg-dark: #000080; --task-bg: #800000; \
+         --task-font-weight: bold"
+    );
+    assert_eq!(row_attribute(&body, &test2.uuid, "style"), "");
+
+    crate::tw::config_set("color", "off", options).unwrap();
+    let mut response = rocket_client.get("/").dispatch();
+    let body = response.body_string().unwrap();
+    assert_eq!(row_attribute(&body, &test.uuid, "class"), "");
+    assert_eq!(row_attribute(&body, &test.uuid, "style"), "");
+
+    // foreground colors are only set where they are readable
+    let style = |color: &str| color.parse::<crate::colors::Color>().unwrap().style();
+    assert_eq!(style("blue"), "--task-fg-light: #000080");
+    assert_eq!(style("bright white"), "--task-fg-dark: #ffffff");
+    assert_eq!(
+        style("yellow"),
+        "--task-fg-light: #808000; --task-fg-dark: #808000"
+    );
+}
+
+#[rstest::rstest]
+fn test_keymap(run_opts: crate::run_opts::RunOpts) {
+    crate::tw::config_set("taskboard.keymap.done", "x", &run_opts).unwrap();
+    let rocket_client = rocket_client(run_opts);
+
+    let mut response = rocket_client.get("/keymap").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    let body = response.body_string().unwrap();
+    assert!(body.starts_with(&"[{\"action\":\"down\",\"key\":\"j\""));
+    assert!(body.contains(&"{\"action\":\"done\",\"key\":\"x\""));
+
+    // Changes in taskrc apply without restart
+    crate::tw::config_set(
+        "taskboard.keymap.done",
+        "shift+Delete",
+        client_options(&rocket_client),
+    )
+    .unwrap();
+    let mut response = rocket_client.get("/keymap").dispatch();
+    assert!(response
+        .body_string()
+        .unwrap()
+        .contains(&"{\"action\":\"done\",\"key\":\"shift+Delete\""));
+
+    let mut response = rocket_client.get("/").dispatch();
+    assert!(!response.body_string().unwrap().contains("data-keymap"));
+
+    let mut response = rocket_client.get("/reports/list").dispatch();
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(rocket::http::ContentType::JSON)
+    );
+    assert!(response
+        .body_string()
+        .unwrap()
+        .contains(&"{\"name\":\"next\",\"description\":"));
+}
+
 // TODO test for empty reports
@@ -20,6 +20,9 @@ pub enum AttributeType {
     Numeric,
     #[strum(serialize = "string")]
     String,
+    #[strum(serialize = "duration")]
+    Duration,
+    /// UDA with missing or unknown `uda.<name>.type`
     #[strum(serialize = "<type>")]
     Uda,
 }
@@ -34,25 +37,217 @@ pub struct ColumnType {
 struct Task {
     attributes: Vec<String>,
     uuid: String,
+    annotations: Vec<Annotation>,
+    /// RFC 3339 timestamp of date columns, whatever their format
+    timestamps: Vec<Option<String>>,
+    /// RFC 3339 timestamp of last modification, to detect concurrent edits
+    modified: Option<String>,
+    /// Matching taskwarrior color rules, highest precedence first
+    color_rules: Vec<String>,
+    /// Custom properties from colors of matching rules
+    color_style: Option<String>,
+}
+
+/// Format of dates in task export and undo data
+static DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+
+/// Parse an exported value of an `AttributeType::DateTime` attribute
+pub fn parse_date(s: &str) -> anyhow::Result<DateTime> {
+    crate::dates::parse_iso(s)
+}
+
+pub fn format_date(date: &DateTime) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// Get date in local timezone
+pub fn local_date(date: &DateTime) -> chrono::NaiveDate {
+    date.with_timezone(&chrono::Local).naive_local().date()
+}
+
+fn deserialize_date<'de, D>(deserializer: D) -> Result<Option<DateTime>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: Option<String> = serde::Deserialize::deserialize(deserializer)?;
+    s.map(|s| parse_date(&s).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Dependencies are exported as a comma separated string by taskwarrior < 2.6, and as an array since
+fn deserialize_depends<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Depends {
+        String(String),
+        Array(Vec<String>),
+    }
+
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        Depends::String(s) => s
+            .split(',')
+            .filter(|u| !u.is_empty())
+            .map(str::to_string)
+            .collect(),
+        Depends::Array(a) => a,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Annotation {
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub entry: Option<DateTime>,
+    pub description: String,
+}
+
+/// Task as exported by `task export`
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ExportedTask {
+    pub uuid: String,
+    pub description: String,
+    pub status: String,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub entry: Option<DateTime>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub modified: Option<DateTime>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub start: Option<DateTime>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub end: Option<DateTime>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub due: Option<DateTime>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub scheduled: Option<DateTime>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub wait: Option<DateTime>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_depends")]
+    pub depends: Vec<String>,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    #[serde(default)]
+    pub urgency: f64,
+    /// All other attributes, including UDAs
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+impl ExportedTask {
+    /// Get value of any attribute as a string, if set
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        match name {
+            "uuid" => Some(self.uuid.clone()),
+            "description" => Some(self.description.clone()),
+            "status" => Some(self.status.clone()),
+            "project" => self.project.clone(),
+            "entry" => self.entry.as_ref().map(format_date),
+            "modified" => self.modified.as_ref().map(format_date),
+            "start" => self.start.as_ref().map(format_date),
+            "end" => self.end.as_ref().map(format_date),
+            "due" => self.due.as_ref().map(format_date),
+            "scheduled" => self.scheduled.as_ref().map(format_date),
+            "wait" => self.wait.as_ref().map(format_date),
+            "tags" if !self.tags.is_empty() => Some(self.tags.join(",")),
+            "tags" => None,
+            "depends" if !self.depends.is_empty() => Some(self.depends.join(",")),
+            "depends" => None,
+            "urgency" => Some(self.urgency.to_string()),
+            _ => match self.other.get(name) {
+                None | Some(serde_json::Value::Null) => None,
+                Some(serde_json::Value::String(s)) => Some(s.clone()),
+                Some(v) => Some(v.to_string()),
+            },
+        }
+    }
+}
+
+/// Filter, sort and columns applied on top of a report definition
+#[derive(Debug, Default, FromForm, serde::Deserialize, serde::Serialize)]
+pub struct ReportOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// Comma separated column list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<String>,
+}
+
+impl ReportOverrides {
+    fn non_empty(value: &Option<String>) -> Option<&str> {
+        value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+    }
+
+    pub fn filter(&self) -> Option<&str> {
+        Self::non_empty(&self.filter)
+    }
+
+    pub fn sort(&self) -> Option<&str> {
+        Self::non_empty(&self.sort)
+    }
+
+    pub fn columns(&self) -> Option<&str> {
+        Self::non_empty(&self.columns)
+    }
+}
+
+/// Replace report columns, keeping labels of the columns already present
+pub fn override_columns(
+    columns: &str,
+    report_columns: &[String],
+    report_labels: &[String],
+) -> (Vec<String>, Vec<String>) {
+    columns
+        .split(',')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(|c| {
+            let label = report_columns
+                .iter()
+                .position(|rc| rc == c)
+                .and_then(|i| report_labels.get(i))
+                .cloned()
+                .unwrap_or_else(|| c.to_string());
+            (c.to_string(), label)
+        })
+        .unzip()
 }
 
 #[derive(serde::Serialize)]
 pub struct Report {
     column_types: Vec<ColumnType>,
+    /// Attribute edited from each column, if its displayed value can be sent back as is
+    attributes: Vec<Option<String>>,
     labels: Vec<String>,
     tasks: Vec<Task>,
 }
 
 lazy_static! {
-    // TODO invalidate this when taskrc is changed (possible new UDA attributes?)
-    static ref COLUMNS_NAME_TO_TYPE: HashMap<String, ColumnType> =
-        build_column_name_to_type_map().unwrap();
+    // TODO invalidate this when taskrc is changed outside of task-board
+    static ref COLUMNS_NAME_TO_TYPE: std::sync::RwLock<HashMap<String, ColumnType>> =
+        std::sync::RwLock::new(HashMap::new());
 }
 
-fn build_column_name_to_type_map() -> anyhow::Result<HashMap<String, ColumnType>> {
+/// Build the column type map, at startup and when UDAs are added, changed or removed
+pub fn refresh_column_types(options: &RunOpts) -> anyhow::Result<()> {
+    let column_types = build_column_name_to_type_map(options)?;
+    *COLUMNS_NAME_TO_TYPE.write().unwrap() = column_types;
+    Ok(())
+}
+
+fn build_column_name_to_type_map(options: &RunOpts) -> anyhow::Result<HashMap<String, ColumnType>> {
     let mut r = HashMap::new();
 
-    let output = invoke_internal(&["columns"], None, true)?;
+    let config = config(options)?;
+    let output = invoke_internal(&["columns"], Some(options), true)?;
     let mut output_lines = output.lines();
 
     // Compute offset for each column from first line (labels)
@@ -96,10 +291,19 @@ fn build_column_name_to_type_map() -> anyhow::Result<HashMap<String, ColumnType>
         }
 
         let (base_column_name, column_type) = if !column_attributes[0].is_empty() {
-            let type_ = AttributeType::from_str(&column_attributes[1])?;
+            let base_column_name = column_attributes[0].clone();
+            let type_ = match column_attributes[1].as_str() {
+                _ if base_column_name == "recur" => AttributeType::Duration,
+                // UDA types are not printed, get them from configuration
+                "<type>" => config
+                    .get(&format!("uda.{}.type", base_column_name))
+                    .and_then(|t| AttributeType::from_str(t).ok())
+                    .unwrap_or(AttributeType::Uda),
+                t => AttributeType::from_str(t)?,
+            };
             let read_only = column_attributes[2] == "Read Only";
             let column_type = ColumnType { type_, read_only };
-            (column_attributes[0].clone(), column_type)
+            (base_column_name, column_type)
         } else {
             prev_parsed_line.unwrap()
         };
@@ -120,19 +324,117 @@ fn build_column_name_to_type_map() -> anyhow::Result<HashMap<String, ColumnType>
     Ok(r)
 }
 
+/// Description column formats that print annotations on additional lines, which we can not parse
+static DESCRIPTION_MULTILINE_FORMATS: [&str; 3] =
+    ["description", "description.combined", "description.full"];
+/// Replacement format, showing the description alone so that it stays editable
+static DESCRIPTION_SINGLE_LINE_FORMAT: &str = "description.desc";
+
+/// Get attribute to modify when editing a report column, formats that lose information are excluded
+fn column_attribute(column: &str) -> Option<String> {
+    match column.split_once('.') {
+        None if !["description", "depends"].contains(&column) => Some(column.to_string()),
+        Some((attribute, "desc")) if attribute == "description" => Some(attribute.to_string()),
+        Some((attribute, "list")) if attribute == "tags" => Some(attribute.to_string()),
+        _ => None,
+    }
+}
+
+/// Taskwarrior commands and built-in reports reported in metrics, anything else (custom reports,
+/// mistyped commands) is counted as `other` so that labels do not depend on user input
+static METRICS_SUBCOMMANDS: [&str; 63] = [
+    "_columns",
+    "_commands",
+    "_config",
+    "_get",
+    "_ids",
+    "_projects",
+    "_show",
+    "_tags",
+    "_udas",
+    "_unique",
+    "_urgency",
+    "_uuids",
+    "_version",
+    "active",
+    "add",
+    "all",
+    "annotate",
+    "append",
+    "blocked",
+    "blocking",
+    "calc",
+    "calendar",
+    "colors",
+    "columns",
+    "commands",
+    "completed",
+    "config",
+    "context",
+    "count",
+    "delete",
+    "denotate",
+    "diagnostics",
+    "done",
+    "duplicate",
+    "edit",
+    "export",
+    "help",
+    "ids",
+    "import",
+    "information",
+    "list",
+    "log",
+    "long",
+    "ls",
+    "minimal",
+    "modify",
+    "newest",
+    "next",
+    "oldest",
+    "overdue",
+    "prepend",
+    "projects",
+    "ready",
+    "recurring",
+    "show",
+    "start",
+    "stats",
+    "stop",
+    "summary",
+    "tags",
+    "undo",
+    "uuids",
+    "waiting",
+];
+
 static CL_ARGS_READ_ONLY: [&str; 2] = ["rc.recurrence:0", "rc.gc:0"];
 static CL_ARGS_OUTPUT: [&str; 2] = ["rc.verbose=label", "limit:4294967296"]; // 2^32
 
+/// Get type of a column or attribute from its name
+pub fn column_type(name: &str) -> Option<ColumnType> {
+    COLUMNS_NAME_TO_TYPE.read().unwrap().get(name).cloned()
+}
+
+/// All known column names, including format variants like `due.relative`
+pub fn column_names() -> Vec<String> {
+    let mut names: Vec<String> = COLUMNS_NAME_TO_TYPE
+        .read()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
 fn column_label_to_type(
     label: &str,
     label2column: &HashMap<String, String>,
 ) -> anyhow::Result<ColumnType> {
     match label2column.get(label) {
         None => Err(anyhow::anyhow!("Unknown column label {}", label)),
-        Some(c) => COLUMNS_NAME_TO_TYPE
-            .get(c)
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("Unknown column {}", c)),
+        Some(c) => column_type(c).ok_or_else(|| anyhow::anyhow!("Unknown column {}", c)),
     }
 }
 
@@ -150,6 +452,9 @@ fn task_output(
         if let Some(task_data_dir) = &opts.task_data_dir {
             cmd.env("TASKDATA", task_data_dir);
         }
+        if let Some(task_rc) = &opts.task_rc {
+            cmd.env("TASKRC", task_rc);
+        }
     }
 
     let output = cmd.output()?;
@@ -157,14 +462,32 @@ fn task_output(
     //println!("task {}\n{:?}", cmd_args.join(" "), output);
 
     let ts_after = std::time::Instant::now();
-    log::debug!(
-        "Command took {}ms to run",
-        ts_after.duration_since(ts_before).as_millis()
-    );
+    let duration = ts_after.duration_since(ts_before);
+    log::debug!("Command took {}ms to run", duration.as_millis());
+    crate::metrics::observe_task_invocation(subcommand(cmd_args), duration, output.status);
 
     Ok(output)
 }
 
+/// Guess task subcommand from its arguments, skipping configuration overrides and filter,
+/// unknown subcommands are reported as `other`
+fn subcommand<'a>(cmd_args: &[&'a str]) -> &'a str {
+    cmd_args
+        .iter()
+        .find(|a| {
+            !a.starts_with("rc.")
+                && !a.contains(':')
+                && !a.starts_with('+')
+                && !a.starts_with('-')
+                && !a.chars().next().map_or(true, |c| c.is_ascii_digit())
+                && a.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !["and", "or", "xor"].contains(*a)
+        })
+        .copied()
+        .filter(|a| METRICS_SUBCOMMANDS.contains(a))
+        .unwrap_or("other")
+}
+
 fn invoke_internal(
     args: &[&str],
     options: Option<&RunOpts>,
@@ -213,6 +536,95 @@ pub fn invoke_external(args: &[&str], options: &RunOpts) -> anyhow::Result<(i32,
     }
 }
 
+/// Set a configuration entry in taskrc, or remove it if the value is empty
+pub fn config_set(key: &str, value: &str, options: &RunOpts) -> anyhow::Result<()> {
+    let mut args = vec!["rc.confirmation:off", "config", key];
+    if !value.is_empty() {
+        args.push(value);
+    }
+    let (code, output) = invoke_external(&args, options)?;
+    // Removing a missing entry fails
+    if code != 0 && !value.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Failed to set {} (code {}): {}",
+            key,
+            code,
+            output
+        ));
+    }
+    Ok(())
+}
+
+/// Add annotation to a task, the text is passed verbatim and may span several lines
+pub fn annotate(uuid: &str, text: &str, options: &RunOpts) -> anyhow::Result<(i32, String)> {
+    invoke_external(&[uuid, "annotate", "--", text], options)
+}
+
+/// Remove annotation from a task
+pub fn denotate(uuid: &str, text: &str, options: &RunOpts) -> anyhow::Result<(i32, String)> {
+    invoke_external(&[uuid, "denotate", "--", text], options)
+}
+
+/// Replace text of an annotation with a single import of the task, keeping its entry timestamp
+pub fn edit_annotation(
+    uuid: &str,
+    old: &str,
+    text: &str,
+    options: &RunOpts,
+) -> anyhow::Result<(i32, String)> {
+    let output = invoke_internal(&["rc.json.array:on", uuid, "export"], Some(options), true)?;
+    let mut tasks: Vec<serde_json::Value> = serde_json::from_str(&output)?;
+    let task = tasks
+        .iter_mut()
+        .find(|t| t["uuid"] == uuid)
+        .and_then(serde_json::Value::as_object_mut)
+        .ok_or_else(|| anyhow::anyhow!("No task with UUID {}", uuid))?;
+    let annotation = task
+        .get_mut("annotations")
+        .and_then(serde_json::Value::as_array_mut)
+        .and_then(|a| a.iter_mut().find(|a| a["description"] == old))
+        .ok_or_else(|| anyhow::anyhow!("No annotation {:?} in task {}", old, uuid))?;
+    annotation["description"] = text.into();
+    // Computed attributes
+    task.remove("id");
+    task.remove("urgency");
+
+    let mut import_file = tempfile::NamedTempFile::new()?;
+    serde_json::to_writer(&mut import_file, &tasks)?;
+    let import_filepath = import_file
+        .path()
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid temporary file path"))?;
+    invoke_external(&["import", import_filepath], options)
+}
+
+pub fn count(filter: &[&str], options: &RunOpts) -> anyhow::Result<u64> {
+    let mut args = filter.to_vec();
+    args.push("count");
+    let output = invoke_internal(&args, Some(options), true)?;
+
+    Ok(output.trim().parse()?)
+}
+
+/// UUIDs of tasks matching filter
+pub fn uuids(filter: &[&str], options: &RunOpts) -> anyhow::Result<Vec<String>> {
+    let mut args = filter.to_vec();
+    args.push("_uuids");
+    let output = invoke_internal(&args, Some(options), true)?;
+
+    Ok(output.lines().map(str::to_string).collect())
+}
+
+/// Check that a string is a full UUID (8-4-4-4-12 hex digits), and not an ID or ID range
+pub fn is_uuid(s: &str) -> bool {
+    let groups: Vec<&str> = s.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip(&[8, 4, 4, 4, 12])
+            .all(|(g, l)| g.len() == *l && g.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
 #[allow(dead_code)]
 fn show(what: &str, options: &RunOpts) -> anyhow::Result<Vec<String>> {
     let args = vec!["show", what];
@@ -234,8 +646,144 @@ fn show(what: &str, options: &RunOpts) -> anyhow::Result<Vec<String>> {
     Err(anyhow::anyhow!("Unexpected output for {:?}", args))
 }
 
+/// Get whole taskwarrior configuration
+pub fn config(options: &RunOpts) -> anyhow::Result<HashMap<String, String>> {
+    let output = invoke_internal(&["_show"], Some(options), true)?;
+
+    Ok(output
+        .lines()
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect())
+}
+
+/// Evaluate an expression with `task calc`, dates are resolved to local ISO 8601 format
+pub fn calc(expression: &str, options: &RunOpts) -> anyhow::Result<String> {
+    let mut args = vec!["rc.dateformat:Y-M-DTH:N:S", "calc"];
+    args.extend(expression.split_whitespace());
+    let output = invoke_internal(&args, Some(options), true)?;
+
+    Ok(output.trim().to_string())
+}
+
+/// Check a new attribute value against its type, and allowed values for UDAs
+pub fn validate_value(attribute: &str, value: &str, options: &RunOpts) -> anyhow::Result<()> {
+    let column_type =
+        column_type(attribute).ok_or_else(|| anyhow::anyhow!("Unknown attribute {}", attribute))?;
+    if column_type.read_only {
+        return Err(anyhow::anyhow!("Attribute {} is read only", attribute));
+    }
+    if value.is_empty() {
+        if attribute == "tags" {
+            return Err(anyhow::anyhow!("Missing tag change"));
+        }
+        // Removes the attribute
+        return Ok(());
+    }
+    match column_type.type_ {
+        AttributeType::Numeric if value.parse::<f64>().is_err() => {
+            return Err(anyhow::anyhow!("Invalid number {:?}", value));
+        }
+        AttributeType::DateTime => {
+            crate::dates::resolve(value, options)?;
+        }
+        _ if attribute == "tags" => {
+            // Tags are added or removed one at a time
+            let valid = (value.starts_with('+') || value.starts_with('-'))
+                && value.len() > 1
+                && !value[1..].contains(|c: char| c.is_whitespace() || c == '+' || c == '-');
+            if !valid {
+                return Err(anyhow::anyhow!("Invalid tag change {:?}", value));
+            }
+        }
+        _ => {}
+    }
+    if let Some(values) = config(options)?.get(&format!("uda.{}.values", attribute)) {
+        if !values.split(',').any(|v| v == value) {
+            return Err(anyhow::anyhow!(
+                "Invalid value {:?} for {}, allowed values are {}",
+                value,
+                attribute,
+                values
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Get names of user defined attributes
+pub fn udas(options: &RunOpts) -> anyhow::Result<Vec<String>> {
+    let output = invoke_internal(&["_udas"], Some(options), true)?;
+
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Get annotations of a task
+pub fn annotations(uuid: &str, options: &RunOpts) -> anyhow::Result<Vec<Annotation>> {
+    Ok(export(&[uuid], options)?
+        .into_iter()
+        .find(|t| t.uuid == uuid)
+        .ok_or_else(|| anyhow::anyhow!("No task with UUID {}", uuid))?
+        .annotations)
+}
+
+/// Get path of the taskwarrior data directory
+pub fn data_location(options: &RunOpts) -> anyhow::Result<std::path::PathBuf> {
+    if let Some(task_data_dir) = &options.task_data_dir {
+        return Ok(task_data_dir.into());
+    }
+    let location = dom_get("rc.data.location", options)?.join(",");
+    match (location.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(location), Some(home)) => Ok(std::path::Path::new(&home).join(location)),
+        _ => Ok(location.into()),
+    }
+}
+
+/// Version of the task data, changing whenever taskwarrior writes to its data directory
+pub fn data_version(options: &RunOpts) -> anyhow::Result<String> {
+    let mut latest = std::time::UNIX_EPOCH;
+    let mut size = 0;
+    for entry in std::fs::read_dir(data_location(options)?)? {
+        let metadata = entry?.metadata()?;
+        latest = latest.max(metadata.modified()?);
+        size += metadata.len();
+    }
+    Ok(format!(
+        "{}-{}",
+        latest.duration_since(std::time::UNIX_EPOCH)?.as_nanos(),
+        size
+    ))
+}
+
+/// Get tasks matching filter with all their attributes
+pub fn export(filter: &[&str], options: &RunOpts) -> anyhow::Result<Vec<ExportedTask>> {
+    let mut args = vec!["rc.json.array:on"];
+    args.extend(filter);
+    args.push("export");
+    let output = invoke_internal(&args, Some(options), true)?;
+
+    if output.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(serde_json::from_str(&output)?)
+}
+
 fn dom_get(what: &str, options: &RunOpts) -> anyhow::Result<Vec<String>> {
-    let args = vec!["_get", what];
+    dom_get_with_config(&[], what, options)
+}
+
+fn dom_get_with_config(
+    config_args: &[&str],
+    what: &str,
+    options: &RunOpts,
+) -> anyhow::Result<Vec<String>> {
+    let mut args = config_args.to_vec();
+    args.extend(&["_get", what]);
     let output = invoke_internal(&args, Some(options), false)?;
 
     Ok(output
@@ -275,30 +823,77 @@ fn parse_label_lines(label_lines: [&str; 2]) -> anyhow::Result<(Vec<String>, Vec
     Ok((labels, column_char_offsets))
 }
 
-pub fn report(report: &str, options: &RunOpts) -> anyhow::Result<Report> {
+/// Run report, with `config` the taskwarrior configuration read for the request
+pub fn report(
+    report: &str,
+    config: &HashMap<String, String>,
+    overrides: &ReportOverrides,
+    options: &RunOpts,
+) -> anyhow::Result<Report> {
+    report_with_config(report, config, &[], overrides, options)
+}
+
+/// Run report with configuration overrides, like `rc.report.<name>.filter:...`
+pub fn report_with_config(
+    report: &str,
+    config: &HashMap<String, String>,
+    config_args: &[&str],
+    overrides: &ReportOverrides,
+    options: &RunOpts,
+) -> anyhow::Result<Report> {
     // Get report columns & labels
     // TODO cache this until taskrc is changed
     // with task show data.location + inotify or keep mtime
     let column_arg = format!("rc.report.{}.columns", report);
     let label_arg = format!("rc.report.{}.labels", report);
-    let report_columns = dom_get(&column_arg, options)?;
+    let (report_columns, report_labels) = {
+        let columns = dom_get_with_config(config_args, &column_arg, options)?;
+        let labels = dom_get_with_config(config_args, &label_arg, options)?;
+        match overrides.columns() {
+            Some(override_) => override_columns(override_, &columns, &labels),
+            None => (columns, labels),
+        }
+    };
+    let report_columns: Vec<String> = report_columns
+        .into_iter()
+        .map(|c| {
+            if DESCRIPTION_MULTILINE_FORMATS.contains(&c.as_str()) {
+                // Annotations are fetched separately
+                DESCRIPTION_SINGLE_LINE_FORMAT.to_string()
+            } else {
+                c
+            }
+        })
+        .collect();
     log::trace!("report_columns = {:?}", report_columns);
-    let report_labels = dom_get(&label_arg, options)?;
     log::trace!("report_labels = {:?}", report_labels);
     assert!(report_labels.len() == report_columns.len());
 
     // Prepend UUID to report columns & labels
-    let mut args = vec![report];
+    let mut args = config_args.to_vec();
+    args.push(report);
     let custom_columns_arg = format!("{}:uuid,{}", column_arg, report_columns.join(","));
     args.push(&custom_columns_arg);
     let custom_labels_arg = format!("{}:UUID,{}", label_arg, report_labels.join(","));
     args.push(&custom_labels_arg);
+    let custom_sort_arg = overrides
+        .sort()
+        .map(|sort| format!("rc.report.{}.sort:{}", report, sort));
+    if let Some(custom_sort_arg) = &custom_sort_arg {
+        args.push(custom_sort_arg);
+    }
+    let filter_args = match overrides.filter() {
+        Some(filter) => shell_words::split(filter)?,
+        None => vec![],
+    };
+    args.extend(filter_args.iter().map(String::as_str));
     let output = invoke_internal(&args, Some(options), false)?;
 
     // Empty report case
     if output.is_empty() {
         return Ok(Report {
             column_types: vec![],
+            attributes: vec![],
             labels: vec![],
             tasks: vec![],
         });
@@ -348,8 +943,13 @@ pub fn report(report: &str, options: &RunOpts) -> anyhow::Result<Report> {
         );
 
         report_tasks.push(Task {
+            timestamps: vec![None; task_attributes.len()],
             attributes: task_attributes,
             uuid,
+            annotations: vec![],
+            modified: None,
+            color_rules: vec![],
+            color_style: None,
         });
     }
 
@@ -367,10 +967,51 @@ pub fn report(report: &str, options: &RunOpts) -> anyhow::Result<Report> {
         .iter()
         .map(|c| column_label_to_type(c, &label2column).unwrap()) // TODO remove unwrap
         .collect();
+    let attributes = present_labels
+        .iter()
+        .map(|l| label2column.get(l).and_then(|c| column_attribute(c)))
+        .collect();
+    // Get annotations and raw dates
+    let date_attributes: Vec<Option<&str>> = present_labels
+        .iter()
+        .zip(column_types.iter())
+        .map(|(l, t)| match t.type_ {
+            AttributeType::DateTime => label2column.get(l).and_then(|c| c.split('.').next()),
+            _ => None,
+        })
+        .collect();
+    let uuids: Vec<&str> = report_tasks.iter().map(|t| t.uuid.as_str()).collect();
+    let mut uuid2task: HashMap<String, ExportedTask> = if uuids.is_empty() {
+        HashMap::new()
+    } else {
+        export(&uuids, options)?
+            .into_iter()
+            .map(|t| (t.uuid.clone(), t))
+            .collect()
+    };
+    let dependencies = crate::colors::Dependencies::new(uuid2task.values());
+    let color_rules = crate::colors::Rules::new(config);
+    for task in &mut report_tasks {
+        if let Some(exported) = uuid2task.remove(&task.uuid) {
+            task.color_rules = color_rules.matching(&exported, &dependencies);
+            task.color_style = color_rules.style(&task.color_rules);
+            task.timestamps = date_attributes
+                .iter()
+                .map(|a| {
+                    let date = parse_date(&exported.attribute((*a)?)?).ok()?;
+                    Some(crate::dates::timestamp(&date))
+                })
+                .collect();
+            task.modified = exported.modified.as_ref().map(crate::dates::timestamp);
+            task.annotations = exported.annotations;
+        }
+    }
+
     log::trace!("column_types ({}) = {:?}", column_types.len(), column_types);
 
     Ok(Report {
         column_types,
+        attributes,
         labels: present_labels,
         tasks: report_tasks,
     })
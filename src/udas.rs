@@ -0,0 +1,86 @@
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+/// UDA types supported by taskwarrior
+static UDA_TYPES: [&str; 4] = ["string", "numeric", "date", "duration"];
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct Uda {
+    name: String,
+    #[serde(rename = "type")]
+    type_: String,
+    #[serde(default)]
+    label: String,
+    /// Comma separated allowed values, empty to allow any value
+    #[serde(default)]
+    values: String,
+    #[serde(default)]
+    default: String,
+    /// Number of non deleted tasks with this attribute set
+    #[serde(default, skip_deserializing)]
+    count: u64,
+}
+
+/// List UDAs with their configuration and usage count
+pub fn udas(options: &RunOpts) -> anyhow::Result<Vec<Uda>> {
+    let config = tw::config(options)?;
+    let mut names: Vec<String> = tw::udas(options)?;
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let get = |key: &str| {
+                config
+                    .get(&format!("uda.{}.{}", name, key))
+                    .cloned()
+                    .unwrap_or_default()
+            };
+            let count = tw::count(&["status.not:deleted", &format!("{}.any:", name)], options)?;
+            Ok(Uda {
+                type_: get("type"),
+                label: get("label"),
+                values: get("values"),
+                default: get("default"),
+                count,
+                name,
+            })
+        })
+        .collect()
+}
+
+fn check(uda: &Uda, options: &RunOpts) -> anyhow::Result<()> {
+    let mut chars = uda.name.chars();
+    if !chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+        || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(anyhow::anyhow!("Invalid UDA name {:?}", uda.name));
+    }
+    if tw::column_type(&uda.name).is_some() && !tw::udas(options)?.contains(&uda.name) {
+        return Err(anyhow::anyhow!(
+            "{:?} is a core attribute, not an UDA",
+            uda.name
+        ));
+    }
+    if !UDA_TYPES.contains(&uda.type_.as_str()) {
+        return Err(anyhow::anyhow!("Invalid UDA type {:?}", uda.type_));
+    }
+    Ok(())
+}
+
+/// Create or update an UDA, then refresh column types so it can be edited at once
+pub fn save(uda: &Uda, options: &RunOpts) -> anyhow::Result<()> {
+    check(uda, options)?;
+    let entries = [
+        ("type", &uda.type_),
+        ("label", &uda.label),
+        ("values", &uda.values),
+        ("default", &uda.default),
+    ];
+    for (key, value) in &entries {
+        tw::config_set(&format!("uda.{}.{}", uda.name, key), value.trim(), options)?;
+    }
+    if !options.dry_run {
+        tw::refresh_column_types(options)?;
+    }
+    Ok(())
+}
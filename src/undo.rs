@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+/// Task state as serialized in undo.data (taskwarrior FF4 format)
+type TaskState = BTreeMap<String, String>;
+
+#[derive(Debug, serde::Serialize)]
+pub struct AttributeChange {
+    attribute: String,
+    old: Option<String>,
+    new: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct HistoryEntry {
+    time: Option<tw::DateTime>,
+    changes: Vec<AttributeChange>,
+}
+
+/// Decode value escaped by taskwarrior JSON encoding and FF4 entities
+fn decode_value(value: &str) -> String {
+    let value = value
+        .replace("&open;", "[")
+        .replace("&close;", "]")
+        .replace("&dquot;", "\"");
+    let mut r = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            r.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => r.push('\n'),
+            Some('t') => r.push('\t'),
+            Some('r') => r.push('\r'),
+            Some('b') => r.push('\u{8}'),
+            Some('f') => r.push('\u{c}'),
+            Some('u') => {
+                let code: String = chars.by_ref().take(4).collect();
+                if let Some(c) = u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+                {
+                    r.push(c);
+                }
+            }
+            Some(c) => r.push(c),
+            None => r.push('\\'),
+        }
+    }
+    r
+}
+
+/// Parse a task line in FF4 format: `[name:"value" name2:"value2"]`
+pub fn parse_ff4(line: &str) -> anyhow::Result<TaskState> {
+    let inner = line
+        .trim()
+        .strip_prefix('[')
+        .and_then(|l| l.strip_suffix(']'))
+        .ok_or_else(|| anyhow::anyhow!("Invalid FF4 line {:?}", line))?;
+
+    let mut r = TaskState::new();
+    let mut rest = inner.trim_start();
+    while !rest.is_empty() {
+        let (name, value_start) = rest
+            .split_once(":\"")
+            .ok_or_else(|| anyhow::anyhow!("Invalid FF4 attribute in {:?}", line))?;
+
+        // Find closing quote, skipping escaped ones
+        let mut value_end = None;
+        let mut escaped = false;
+        for (i, c) in value_start.char_indices() {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => {
+                    value_end = Some(i);
+                    break;
+                }
+                _ => escaped = false,
+            }
+        }
+        let value_end =
+            value_end.ok_or_else(|| anyhow::anyhow!("Unterminated FF4 value in {:?}", line))?;
+
+        r.insert(
+            name.trim().to_string(),
+            decode_value(&value_start[..value_end]),
+        );
+        rest = value_start[value_end + 1..].trim_start();
+    }
+
+    Ok(r)
+}
+
+/// Parse FF4 date value (epoch timestamp)
+fn parse_epoch(value: &str) -> Option<tw::DateTime> {
+    let ts = value.parse().ok()?;
+    chrono::TimeZone::timestamp_opt(&chrono::Utc, ts, 0).single()
+}
+
+/// Format attribute value for display, converting dates to local time
+fn display_value(attribute: &str, value: &str) -> String {
+    match tw::column_type(attribute) {
+        Some(tw::ColumnType {
+            type_: tw::AttributeType::DateTime,
+            ..
+        }) => match parse_epoch(value) {
+            Some(date) => crate::detail::format_local_date(&date),
+            None => value.to_string(),
+        },
+        _ => value.to_string(),
+    }
+}
+
+fn diff(old: Option<&TaskState>, new: &TaskState) -> Vec<AttributeChange> {
+    let empty = TaskState::new();
+    let old = old.unwrap_or(&empty);
+    let mut attributes: Vec<&String> = old.keys().chain(new.keys()).collect();
+    attributes.sort();
+    attributes.dedup();
+
+    attributes
+        .into_iter()
+        .filter(|a| a.as_str() != "modified")
+        .filter(|a| old.get(*a) != new.get(*a))
+        .map(|a| AttributeChange {
+            attribute: a.to_string(),
+            old: old.get(a).map(|v| display_value(a, v)),
+            new: new.get(a).map(|v| display_value(a, v)),
+        })
+        .collect()
+}
+
+/// Transaction of undo.data being read
+#[derive(Default)]
+struct Transaction {
+    time: Option<tw::DateTime>,
+    old: Option<TaskState>,
+    new: Option<TaskState>,
+    /// A line could not be parsed
+    invalid: bool,
+}
+
+impl Transaction {
+    /// Get history entry if this transaction changed the task
+    fn entry(self, uuid: &str) -> Option<HistoryEntry> {
+        if self.invalid {
+            return None;
+        }
+        let new = self.new?;
+        if new.get("uuid").map(String::as_str) != Some(uuid) {
+            return None;
+        }
+        Some(HistoryEntry {
+            time: self.time,
+            changes: diff(self.old.as_ref(), &new),
+        })
+    }
+}
+
+/// Get change history of a task from undo.data, oldest first
+///
+/// Returns `None` if the data directory uses the TaskChampion storage of taskwarrior 2.6+, which has
+/// no undo.data file. Unparsable entries are logged and skipped.
+pub fn task_history(uuid: &str, options: &RunOpts) -> anyhow::Result<Option<Vec<HistoryEntry>>> {
+    let data_dir = tw::data_location(options)?;
+    let undo_filepath = data_dir.join("undo.data");
+    let undo_file = match std::fs::File::open(&undo_filepath) {
+        Ok(undo_file) => undo_file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if data_dir.join("taskchampion.sqlite3").exists() {
+                log::warn!(
+                    "Task history is not supported with TaskChampion storage in {:?}",
+                    data_dir
+                );
+                return Ok(None);
+            }
+            log::debug!("No undo data at {:?}", undo_filepath);
+            return Ok(Some(vec![]));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut r = Vec::new();
+    let mut transaction = Transaction::default();
+    for (i, line) in std::io::BufRead::lines(std::io::BufReader::new(undo_file)).enumerate() {
+        let line = line?;
+        match line.split_once(' ') {
+            None if line == "---" => r.extend(std::mem::take(&mut transaction).entry(uuid)),
+            Some(("time", value)) => transaction.time = parse_epoch(value.trim()),
+            Some((kind @ ("old" | "new"), value)) => match parse_ff4(value) {
+                Ok(state) if kind == "old" => transaction.old = Some(state),
+                Ok(state) => transaction.new = Some(state),
+                Err(e) => {
+                    log::warn!("Skipping {:?} line {}: {}", undo_filepath, i + 1, e);
+                    transaction.invalid = true;
+                }
+            },
+            _ => {}
+        }
+    }
+    r.extend(transaction.entry(uuid));
+
+    Ok(Some(r))
+}
+
+/// Get changes made to a task after a date, merged per attribute
+pub fn changes_since(
+    uuid: &str,
+    since: &tw::DateTime,
+    options: &RunOpts,
+) -> anyhow::Result<Vec<AttributeChange>> {
+    let mut r: Vec<AttributeChange> = Vec::new();
+    for entry in task_history(uuid, options)?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| e.time.map_or(false, |t| t > *since))
+    {
+        for change in entry.changes {
+            match r.iter_mut().find(|c| c.attribute == change.attribute) {
+                Some(merged) => merged.new = change.new,
+                None => r.push(change),
+            }
+        }
+    }
+    r.retain(|c| c.old != c.new);
+    Ok(r)
+}
@@ -0,0 +1,278 @@
+use crate::run_opts::RunOpts;
+use crate::tw;
+
+/// Names already used by other pages, reports with these names would not be reachable
+static RESERVED_NAMES: [&str; 16] = [
+    "board", "bulk", "calc", "calendar", "charts", "graph", "keymap", "metrics", "projects",
+    "reports", "shell", "static", "task", "udas", "updates", "views",
+];
+
+/// Report name used to preview definitions, only defined by configuration overrides
+static PREVIEW_REPORT: &str = "taskboardpreview";
+
+/// Report definition, as stored in `report.<name>.*` configuration entries
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct ReportDefinition {
+    #[serde(default)]
+    pub description: String,
+    pub columns: Vec<String>,
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub sort: String,
+    #[serde(default)]
+    pub filter: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct ReportInfo {
+    name: String,
+    description: String,
+}
+
+/// Report definition edited from the report editor
+#[derive(serde::Deserialize)]
+pub struct ReportEdit {
+    name: String,
+    #[serde(flatten)]
+    definition: ReportDefinition,
+    /// Only validate and run the report, without saving it
+    #[serde(default)]
+    preview: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct ReportEditResult {
+    dry_run: bool,
+    errors: Vec<String>,
+    report: Option<tw::Report>,
+}
+
+/// Current report with overrides, to be saved under a new name
+#[derive(serde::Deserialize)]
+pub struct SavedView {
+    name: String,
+    #[serde(default)]
+    description: String,
+    report: String,
+    /// Replace an existing report with the same name
+    #[serde(default)]
+    overwrite: bool,
+    #[serde(flatten)]
+    overrides: tw::ReportOverrides,
+}
+
+fn split_list(value: Option<&String>) -> Vec<String> {
+    match value {
+        Some(value) if !value.is_empty() => value.split(',').map(str::to_string).collect(),
+        _ => vec![],
+    }
+}
+
+/// List reports defined in configuration, sorted by name
+pub fn reports(options: &RunOpts) -> anyhow::Result<Vec<ReportInfo>> {
+    Ok(reports_from_config(&tw::config(options)?))
+}
+
+/// List reports defined in already read configuration, sorted by name
+pub fn reports_from_config(config: &std::collections::HashMap<String, String>) -> Vec<ReportInfo> {
+    let mut reports: Vec<ReportInfo> = config
+        .keys()
+        .filter_map(|k| k.strip_prefix("report.")?.strip_suffix(".columns"))
+        .map(|name| ReportInfo {
+            name: name.to_string(),
+            description: config
+                .get(&format!("report.{}.description", name))
+                .cloned()
+                .unwrap_or_default(),
+        })
+        .collect();
+    reports.sort_by(|a, b| a.name.cmp(&b.name));
+    reports
+}
+
+pub fn report_definition(name: &str, options: &RunOpts) -> anyhow::Result<ReportDefinition> {
+    let config = tw::config(options)?;
+    let get = |key: &str| config.get(&format!("report.{}.{}", name, key));
+    let columns = split_list(get("columns"));
+    if columns.is_empty() {
+        return Err(anyhow::anyhow!("No report named {:?}", name));
+    }
+    let mut labels = split_list(get("labels"));
+    labels.resize(columns.len(), String::new());
+    Ok(ReportDefinition {
+        description: get("description").cloned().unwrap_or_default(),
+        columns,
+        labels,
+        sort: get("sort").cloned().unwrap_or_default(),
+        filter: get("filter").cloned().unwrap_or_default(),
+    })
+}
+
+fn check_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(anyhow::anyhow!("Invalid report name {:?}", name));
+    }
+    if RESERVED_NAMES.contains(&name) {
+        return Err(anyhow::anyhow!("Report name {:?} is reserved", name));
+    }
+    Ok(())
+}
+
+/// Trim list entries, and use column names for missing labels
+fn normalize(definition: &ReportDefinition) -> ReportDefinition {
+    let trim_list = |l: &[String]| -> Vec<String> {
+        l.iter()
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty())
+            .collect()
+    };
+    let columns = trim_list(&definition.columns);
+    let mut labels = trim_list(&definition.labels);
+    if labels.is_empty() {
+        labels = columns.clone();
+    }
+    ReportDefinition {
+        description: definition.description.trim().to_string(),
+        columns,
+        labels,
+        sort: definition.sort.trim().to_string(),
+        filter: definition.filter.trim().to_string(),
+    }
+}
+
+fn validate(definition: &ReportDefinition) -> Vec<String> {
+    let mut errors: Vec<String> = definition
+        .columns
+        .iter()
+        .filter(|c| tw::column_type(c).is_none())
+        .map(|c| format!("Unknown column {:?}", c))
+        .collect();
+    if definition.columns.is_empty() {
+        errors.push("No column".to_string());
+    }
+    if definition.columns.len() != definition.labels.len() {
+        errors.push(format!(
+            "{} column(s) but {} label(s)",
+            definition.columns.len(),
+            definition.labels.len()
+        ));
+    }
+    if definition.labels.iter().any(|l| l.contains(',')) {
+        errors.push("Labels can not contain commas".to_string());
+    }
+    errors
+}
+
+/// Validate report definition, then run it or save it
+pub fn edit(edit: &ReportEdit, options: &RunOpts) -> anyhow::Result<ReportEditResult> {
+    let definition = normalize(&edit.definition);
+    let mut errors = validate(&definition);
+    if !edit.preview {
+        if let Err(e) = check_name(&edit.name) {
+            errors.push(e.to_string());
+        }
+    }
+    if !errors.is_empty() {
+        return Ok(ReportEditResult {
+            dry_run: options.dry_run,
+            errors,
+            report: None,
+        });
+    }
+
+    let report = if edit.preview {
+        let config_args = [
+            format!(
+                "rc.report.{}.columns:{}",
+                PREVIEW_REPORT,
+                definition.columns.join(",")
+            ),
+            format!(
+                "rc.report.{}.labels:{}",
+                PREVIEW_REPORT,
+                definition.labels.join(",")
+            ),
+            format!("rc.report.{}.sort:{}", PREVIEW_REPORT, definition.sort),
+            format!("rc.report.{}.filter:{}", PREVIEW_REPORT, definition.filter),
+        ];
+        let config_args: Vec<&str> = config_args.iter().map(String::as_str).collect();
+        let config = tw::config(options)?;
+        match tw::report_with_config(
+            PREVIEW_REPORT,
+            &config,
+            &config_args,
+            &Default::default(),
+            options,
+        ) {
+            Ok(report) => Some(report),
+            Err(e) => {
+                errors.push(e.to_string());
+                None
+            }
+        }
+    } else {
+        save_report_definition(&edit.name, &definition, options)?;
+        None
+    };
+    Ok(ReportEditResult {
+        dry_run: options.dry_run,
+        errors,
+        report,
+    })
+}
+
+/// Write report definition to taskrc, empty values remove the entry
+pub fn save_report_definition(
+    name: &str,
+    definition: &ReportDefinition,
+    options: &RunOpts,
+) -> anyhow::Result<()> {
+    check_name(name)?;
+    if definition.columns.len() != definition.labels.len() {
+        return Err(anyhow::anyhow!("Column and label counts differ"));
+    }
+    let entries = [
+        ("description", definition.description.clone()),
+        ("columns", definition.columns.join(",")),
+        ("labels", definition.labels.join(",")),
+        ("sort", definition.sort.clone()),
+        ("filter", definition.filter.clone()),
+    ];
+    for (key, value) in &entries {
+        tw::config_set(&format!("report.{}.{}", name, key), value, options)?;
+    }
+    Ok(())
+}
+
+/// Save report with its overrides as a new report, existing reports are only replaced if requested
+pub fn save_view(view: &SavedView, options: &RunOpts) -> anyhow::Result<ReportDefinition> {
+    if !view.overwrite && report_definition(&view.name, options).is_ok() {
+        return Err(anyhow::anyhow!("Report {:?} already exists", view.name));
+    }
+    let base = report_definition(&view.report, options)?;
+    let (columns, labels) = match view.overrides.columns() {
+        Some(columns) => tw::override_columns(columns, &base.columns, &base.labels),
+        None => (base.columns, base.labels),
+    };
+    let filter = match view.overrides.filter() {
+        Some(filter) => format!("{} ( {} )", base.filter, filter).trim().to_string(),
+        None => base.filter,
+    };
+    let definition = ReportDefinition {
+        description: if view.description.is_empty() {
+            base.description
+        } else {
+            view.description.clone()
+        },
+        columns,
+        labels,
+        sort: view.overrides.sort().map_or(base.sort, str::to_string),
+        filter,
+    };
+    save_report_definition(&view.name, &definition, options)?;
+    Ok(definition)
+}